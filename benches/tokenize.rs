@@ -0,0 +1,109 @@
+//! Times tokenization with and without `Tokenizer::compile`, on a small
+//! expression language and on one with many keyword rules. Run with
+//! `cargo bench`.
+
+extern crate rb_tokenizer;
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use rb_tokenizer::Tokenizer;
+
+const RUNS: u32 = 20;
+
+fn expression_tokenizer() -> Tokenizer {
+    let mut tokenizer = Tokenizer::new();
+
+    for (symbol, sub_type) in [
+        ("(", "OpenParen"),
+        (")", "CloseParen"),
+        ("[", "OpenBracket"),
+        ("]", "CloseBracket"),
+        (",", "Comma"),
+    ] {
+        tokenizer.add_symbol_rule(symbol, "Braces", Some(sub_type));
+    }
+    for symbol in [
+        "+", "-", "*", "/", "%", "==", "!=", "<", "<=", ">", ">=", "&&", "||", "!", "&", "^", "~",
+        "<<", ">>",
+    ] {
+        tokenizer.add_symbol_rule(symbol, "Operator", None);
+    }
+
+    tokenizer.add_regex_rule(r"^(true|false|null)\b", "Literal", None);
+    tokenizer.add_regex_rule(r#"^`([^`]|\\.)*`"#, "String", None);
+    tokenizer.add_regex_rule(r#"^'([^'\\]|\\.)*'"#, "String", None);
+    tokenizer.add_regex_rule(r#"^"([^"\\]|\\.)*""#, "String", None);
+    tokenizer.add_regex_rule(r"^[a-zA-Z_][a-zA-Z0-9_]*", "Identifier", None);
+    tokenizer.add_regex_rule(r"^\$[a-zA-Z0-9_]*", "Variable", None);
+    tokenizer.add_regex_rule(r"^\|([a-zA-Z][a-zA-Z0-9_]*\:)?", "Pipe", None);
+    tokenizer.add_symbol_rule("|", "Operator", Some("BitwiseOr"));
+    tokenizer.add_regex_rule(r"^-?\d+(\.\d+)?([eE][-+]?\d+)?", "Number", None);
+
+    tokenizer
+}
+
+// One regex rule per keyword before the identifier rule, which is what
+// compiling the grammar pays off for
+fn keyword_tokenizer() -> Tokenizer {
+    let mut tokenizer = Tokenizer::new();
+
+    for keyword in [
+        "as", "break", "const", "continue", "crate", "else", "enum", "extern", "false", "fn",
+        "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref",
+        "return", "self", "static", "struct", "super", "trait", "true", "type", "unsafe", "use",
+        "where", "while",
+    ] {
+        tokenizer.add_regex_rule(&format!(r"{}\b", keyword), "Keyword", Some(keyword));
+    }
+    tokenizer.add_regex_rule(r"[a-zA-Z_][a-zA-Z0-9_]*", "Identifier", None);
+    tokenizer.add_regex_rule(r"\d+", "Number", None);
+    for symbol in [
+        "{", "}", "(", ")", ";", ":", ",", ".", "=", "==", "+", "-", "*", "&", "->",
+    ] {
+        tokenizer.add_symbol_rule(symbol, "Punctuation", None);
+    }
+
+    tokenizer
+}
+
+fn time(tokenizer: &Tokenizer, input: &str) -> Duration {
+    let mut best = Duration::MAX;
+    for _ in 0..RUNS {
+        let start = Instant::now();
+        black_box(tokenizer.tokenize(black_box(input)).unwrap());
+        best = best.min(start.elapsed());
+    }
+    best
+}
+
+fn bench(name: &str, tokenizer: fn() -> Tokenizer, line: &str) {
+    let input = line.repeat(2_000);
+    let uncompiled = tokenizer();
+    let mut compiled = tokenizer();
+    compiled.compile().unwrap();
+    assert_eq!(
+        uncompiled.tokenize(&input).unwrap(),
+        compiled.tokenize(&input).unwrap()
+    );
+
+    println!(
+        "{:<12} uncompiled {:>10.2?}   compiled {:>10.2?}",
+        name,
+        time(&uncompiled, &input),
+        time(&compiled, &input)
+    );
+}
+
+fn main() {
+    bench(
+        "expression",
+        expression_tokenizer,
+        "[1, 2, 3, 4] |map: RAND() * $1 |filter: $1 % 2 == 0 && `raw` != 'x' || true\n",
+    );
+    bench(
+        "keywords",
+        keyword_tokenizer,
+        "pub fn main() { let mut total = 0; for item in items { total = total + item.len(); } }\n",
+    );
+}
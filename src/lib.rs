@@ -2,7 +2,10 @@ pub mod rules;
 pub mod tokens;
 
 pub mod tokenizers;
-pub use tokenizers::Tokenizer;
+pub use tokenizers::{
+    BorrowedTokenStream, ColumnUnit, MatchStrategy, RecoveryPolicy, TokenStream, Tokenizer,
+    WhitespacePolicy,
+};
 
 pub fn add(left: usize, right: usize) -> usize {
     left + right
@@ -1,21 +1,34 @@
-use super::Rule;
+use std::sync::Arc;
+
+use super::{Match, Rule, RuleContext};
 
 use crate::tokens::Token;
+use crate::tokens::TokenKind;
 use crate::tokens::TokenizationError;
 
-pub struct ClosureRule {
-    // cb is a closure that takes a string slice and returns a Result<Option<Token>, TokenizationError>
-    cb: Box<dyn Fn(&str) -> Result<Option<Token>, TokenizationError>>,
+/// `ClosureFn` is the signature of the closures wrapped by `ClosureRule`.
+pub type ClosureFn<K = String> = dyn Fn(&str, &mut RuleContext<'_, '_, K>) -> Result<Option<Match<Token<K>>>, TokenizationError>
+    + Send
+    + Sync;
+
+#[derive(Clone)]
+pub struct ClosureRule<K: TokenKind = String> {
+    // cb is a closure that takes a string slice and the rule context, and returns a Result<Option<Match<Token>>, TokenizationError>
+    cb: Arc<ClosureFn<K>>,
 }
 
-impl ClosureRule {
-    pub fn new(cb: Box<dyn Fn(&str) -> Result<Option<Token>, TokenizationError>>) -> Self {
-        ClosureRule { cb }
+impl<K: TokenKind> ClosureRule<K> {
+    pub fn new(cb: Box<ClosureFn<K>>) -> Self {
+        ClosureRule { cb: cb.into() }
     }
 }
 
-impl Rule for ClosureRule {
-    fn process(&self, input: &str) -> Result<Option<Token>, TokenizationError> {
-        (self.cb)(input)
+impl<K: TokenKind> Rule<K> for ClosureRule<K> {
+    fn process(
+        &self,
+        input: &str,
+        context: &mut RuleContext<'_, '_, K>,
+    ) -> Result<Option<Match<Token<K>>>, TokenizationError> {
+        (self.cb)(input, context)
     }
 }
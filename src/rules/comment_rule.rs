@@ -0,0 +1,221 @@
+use super::{FirstBytes, Match, Rule, RuleContext};
+use crate::tokens::BorrowedToken;
+use crate::tokens::Span;
+use crate::tokens::Token;
+use crate::tokens::TokenKind;
+use crate::tokens::TokenizationError;
+
+/// `COMMENT_TOKEN_TYPE` is the conventional `token_type` of comment tokens when
+/// token types are `String`s. Tokens of this type are trivia, see
+/// `TokenKind::is_trivia`.
+pub const COMMENT_TOKEN_TYPE: &str = "Comment";
+
+/// `LineCommentRule` matches a comment from `prefix` up to the end of the
+/// line. The line break itself is not part of the comment.
+///
+/// Comments are emitted as tokens of type `token_type` unless the rule is
+/// `skipped`.
+#[derive(Clone)]
+pub struct LineCommentRule<K = String> {
+    pub prefix: String,
+    pub token_type: K,
+    pub token_sub_type: Option<String>,
+    skip: bool,
+}
+
+impl<K: TokenKind> LineCommentRule<K> {
+    pub fn new(prefix: &str, token_type: impl Into<K>, token_sub_type: Option<&str>) -> Self {
+        LineCommentRule {
+            prefix: prefix.to_string(),
+            token_type: token_type.into(),
+            token_sub_type: token_sub_type.map(|s| s.to_string()),
+            skip: false,
+        }
+    }
+
+    /// Consumes comments without emitting tokens for them.
+    pub fn skipped(mut self) -> Self {
+        self.skip = true;
+        self
+    }
+
+    // The length of the comment at the start of `input`, if any
+    fn find(&self, input: &str) -> Option<usize> {
+        if self.prefix.is_empty() || !input.starts_with(&self.prefix) {
+            return None;
+        }
+        let end = input.find('\n').unwrap_or(input.len());
+        Some(input[..end].strip_suffix('\r').map_or(end, str::len))
+    }
+}
+
+impl<K: TokenKind> Rule<K> for LineCommentRule<K> {
+    fn process(
+        &self,
+        input: &str,
+        context: &mut RuleContext<'_, '_, K>,
+    ) -> Result<Option<Match<Token<K>>>, TokenizationError> {
+        Ok(self
+            .process_borrowed(input, context)?
+            .map(|m| m.map(Token::from)))
+    }
+
+    fn process_borrowed<'a>(
+        &'a self,
+        input: &'a str,
+        _context: &mut RuleContext<'_, '_, K>,
+    ) -> Result<Option<Match<BorrowedToken<'a, K>>>, TokenizationError> {
+        Ok(self.find(input).map(|len| {
+            comment_match(
+                self.skip,
+                len,
+                BorrowedToken::new(
+                    &self.token_type,
+                    self.token_sub_type.as_deref(),
+                    &input[..len],
+                ),
+            )
+        }))
+    }
+
+    fn first_bytes(&self) -> FirstBytes {
+        FirstBytes::of_str(&self.prefix)
+    }
+}
+
+/// `BlockCommentRule` matches a comment between `start` and `end` delimiters,
+/// which may span several lines.
+///
+/// By default the first `end` closes the comment. A `nested` rule counts the
+/// `start` delimiters inside the comment and only ends it once all of them
+/// are closed, so that `/* a /* b */ c */` is a single comment.
+///
+/// A comment that is still open at the end of the input runs to the end of
+/// the input and is reported as `TokenizationError::Unterminated`, pointing at
+/// its `start` delimiter.
+#[derive(Clone)]
+pub struct BlockCommentRule<K = String> {
+    pub start: String,
+    pub end: String,
+    pub token_type: K,
+    pub token_sub_type: Option<String>,
+    nested: bool,
+    skip: bool,
+}
+
+// The extent of a block comment
+struct Found {
+    len: usize,
+    terminated: bool,
+}
+
+impl<K: TokenKind> BlockCommentRule<K> {
+    pub fn new(
+        start: &str,
+        end: &str,
+        token_type: impl Into<K>,
+        token_sub_type: Option<&str>,
+    ) -> Self {
+        BlockCommentRule {
+            start: start.to_string(),
+            end: end.to_string(),
+            token_type: token_type.into(),
+            token_sub_type: token_sub_type.map(|s| s.to_string()),
+            nested: false,
+            skip: false,
+        }
+    }
+
+    /// Allows comments to nest.
+    pub fn nested(mut self) -> Self {
+        self.nested = true;
+        self
+    }
+
+    /// Consumes comments without emitting tokens for them.
+    pub fn skipped(mut self) -> Self {
+        self.skip = true;
+        self
+    }
+
+    fn find(&self, input: &str) -> Option<Found> {
+        if self.start.is_empty() || self.end.is_empty() || !input.starts_with(&self.start) {
+            return None;
+        }
+
+        let mut depth = 1;
+        let mut offset = self.start.len();
+        while let Some(next) = input[offset..].chars().next() {
+            let rest = &input[offset..];
+            // The end delimiter goes first, for delimiters that are the same
+            if rest.starts_with(&self.end) {
+                offset += self.end.len();
+                depth -= 1;
+                if depth == 0 {
+                    return Some(Found {
+                        len: offset,
+                        terminated: true,
+                    });
+                }
+            } else if self.nested && rest.starts_with(&self.start) {
+                offset += self.start.len();
+                depth += 1;
+            } else {
+                offset += next.len_utf8();
+            }
+        }
+
+        Some(Found {
+            len: input.len(),
+            terminated: false,
+        })
+    }
+}
+
+impl<K: TokenKind> Rule<K> for BlockCommentRule<K> {
+    fn process(
+        &self,
+        input: &str,
+        context: &mut RuleContext<'_, '_, K>,
+    ) -> Result<Option<Match<Token<K>>>, TokenizationError> {
+        Ok(self
+            .process_borrowed(input, context)?
+            .map(|m| m.map(Token::from)))
+    }
+
+    fn process_borrowed<'a>(
+        &'a self,
+        input: &'a str,
+        _context: &mut RuleContext<'_, '_, K>,
+    ) -> Result<Option<Match<BorrowedToken<'a, K>>>, TokenizationError> {
+        let Some(found) = self.find(input) else {
+            return Ok(None);
+        };
+
+        let token = BorrowedToken::new(
+            &self.token_type,
+            self.token_sub_type.as_deref(),
+            &input[..found.len],
+        );
+        let m = comment_match(self.skip, found.len, token);
+        if found.terminated {
+            Ok(Some(m))
+        } else {
+            let span = Span::new(0, self.start.len());
+            let error = TokenizationError::unterminated("block comment", span);
+            Ok(Some(m.with_error(error)))
+        }
+    }
+
+    fn first_bytes(&self) -> FirstBytes {
+        FirstBytes::of_str(&self.start)
+    }
+}
+
+fn comment_match<T>(skip: bool, len: usize, token: T) -> Match<T> {
+    if skip {
+        Match::skip(len)
+    } else {
+        Match::new(len, token)
+    }
+}
@@ -0,0 +1,131 @@
+use std::ops::RangeInclusive;
+
+use regex_syntax::hir::{Class, Hir, HirKind};
+
+/// `FirstBytes` is the set of bytes a rule's matches can start with. The
+/// tokenizer uses it to skip rules that cannot match at a position without
+/// running them.
+///
+/// The set may contain bytes no match starts with, but must contain every
+/// byte one does: a rule is never tried where its set rules it out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FirstBytes([u64; 4]);
+
+impl FirstBytes {
+    /// Every byte, for rules that may match anything.
+    pub fn any() -> Self {
+        FirstBytes([u64::MAX; 4])
+    }
+
+    /// No byte at all, for rules that never match.
+    pub fn none() -> Self {
+        FirstBytes([0; 4])
+    }
+
+    /// The bytes `text` can start with, any byte if it is empty.
+    pub fn of_str(text: &str) -> Self {
+        match text.as_bytes().first() {
+            Some(&byte) => FirstBytes::from_iter([byte]),
+            None => FirstBytes::any(),
+        }
+    }
+
+    /// The bytes the matches of a parsed regex can start with.
+    pub(crate) fn of_hir(hir: &Hir) -> Self {
+        match first_of(hir) {
+            (_, true) => FirstBytes::any(), // May match without consuming a byte
+            (bytes, false) => bytes,
+        }
+    }
+
+    pub fn insert(&mut self, byte: u8) {
+        self.0[byte as usize / 64] |= 1 << (byte % 64);
+    }
+
+    pub fn insert_range(&mut self, range: RangeInclusive<u8>) {
+        for byte in range {
+            self.insert(byte);
+        }
+    }
+
+    pub fn union(mut self, other: FirstBytes) -> Self {
+        for (word, other) in self.0.iter_mut().zip(other.0) {
+            *word |= other;
+        }
+        self
+    }
+
+    pub fn contains(&self, byte: u8) -> bool {
+        self.0[byte as usize / 64] & (1 << (byte % 64)) != 0
+    }
+
+    pub fn is_any(&self) -> bool {
+        *self == FirstBytes::any()
+    }
+}
+
+impl FromIterator<u8> for FirstBytes {
+    fn from_iter<I: IntoIterator<Item = u8>>(bytes: I) -> Self {
+        let mut set = FirstBytes::none();
+        for byte in bytes {
+            set.insert(byte);
+        }
+        set
+    }
+}
+
+// The bytes a match of `hir` can start with, and whether it can be empty, in
+// which case the match may start with whatever follows it
+fn first_of(hir: &Hir) -> (FirstBytes, bool) {
+    match hir.kind() {
+        HirKind::Empty | HirKind::Look(_) => (FirstBytes::none(), true),
+        HirKind::Literal(literal) => match literal.0.first() {
+            Some(&byte) => (FirstBytes::from_iter([byte]), false),
+            None => (FirstBytes::none(), true),
+        },
+        HirKind::Class(Class::Bytes(class)) => {
+            let mut bytes = FirstBytes::none();
+            for range in class.ranges() {
+                bytes.insert_range(range.start()..=range.end());
+            }
+            (bytes, false)
+        }
+        HirKind::Class(Class::Unicode(class)) => {
+            // Leading bytes grow with the code point, so the leading bytes of
+            // a range lie between those of its bounds
+            let mut bytes = FirstBytes::none();
+            for range in class.ranges() {
+                bytes.insert_range(leading_byte(range.start())..=leading_byte(range.end()));
+            }
+            (bytes, false)
+        }
+        HirKind::Repetition(repetition) => {
+            let (bytes, empty) = first_of(&repetition.sub);
+            (bytes, empty || repetition.min == 0)
+        }
+        HirKind::Capture(capture) => first_of(&capture.sub),
+        HirKind::Concat(items) => {
+            let mut bytes = FirstBytes::none();
+            for item in items {
+                let (first, empty) = first_of(item);
+                bytes = bytes.union(first);
+                if !empty {
+                    return (bytes, false);
+                }
+            }
+            (bytes, true)
+        }
+        HirKind::Alternation(alternatives) => alternatives.iter().fold(
+            (FirstBytes::none(), false),
+            |(bytes, empty), alternative| {
+                let (first, can_be_empty) = first_of(alternative);
+                (bytes.union(first), empty || can_be_empty)
+            },
+        ),
+    }
+}
+
+fn leading_byte(c: char) -> u8 {
+    let mut buffer = [0; 4];
+    c.encode_utf8(&mut buffer).as_bytes()[0]
+}
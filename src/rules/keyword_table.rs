@@ -0,0 +1,197 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use super::RuleContext;
+use crate::tokens::{BorrowedToken, TokenKind};
+
+/// `KeywordPredicate` decides whether a contextual keyword is a keyword at the
+/// position it was found at.
+pub type KeywordPredicate<K = String> = dyn Fn(&mut RuleContext<'_, '_, K>) -> bool + Send + Sync;
+
+/// `Keyword` is the type and subtype a word of a `KeywordTable` is given.
+#[derive(Clone)]
+pub struct Keyword<K: TokenKind = String> {
+    pub token_type: K,
+    pub token_sub_type: Option<String>,
+    // Only contextual keywords have one
+    condition: Option<Arc<KeywordPredicate<K>>>,
+}
+
+impl<K: TokenKind> Keyword<K> {
+    /// Whether the keyword only applies when a predicate holds.
+    pub fn is_contextual(&self) -> bool {
+        self.condition.is_some()
+    }
+}
+
+/// `KeywordTable` retypes the tokens of the rule it is attached to (with
+/// `RuleHandle::keywords`) whose value is one of its words. It lets a single
+/// identifier rule recognize every keyword, instead of one rule per keyword
+/// placed before it.
+///
+/// ```
+/// use rb_tokenizer::rules::KeywordTable;
+/// use rb_tokenizer::Tokenizer;
+///
+/// let mut tokenizer = Tokenizer::new();
+/// tokenizer
+///     .add_regex_rule(r"[a-zA-Z_]\w*", "Identifier", None)
+///     .keywords(
+///         KeywordTable::new()
+///             .with("if", "Keyword", Some("If"))
+///             .with("true", "Literal", Some("Boolean")),
+///     );
+///
+/// let tokens = tokenizer.tokenize("if truehearted true").unwrap();
+/// let types: Vec<_> = tokens.iter().map(|t| t.token_type.as_str()).collect();
+/// assert_eq!(types, vec!["Keyword", "Identifier", "Literal"]);
+/// ```
+#[derive(Clone)]
+pub struct KeywordTable<K: TokenKind = String> {
+    words: HashMap<String, Keyword<K>>,
+    case_insensitive: bool,
+}
+
+impl<K: TokenKind> Default for KeywordTable<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: TokenKind> KeywordTable<K> {
+    pub fn new() -> Self {
+        KeywordTable {
+            words: HashMap::new(),
+            case_insensitive: false,
+        }
+    }
+
+    /// Matches words regardless of case, as in SQL or Pascal. Case is folded
+    /// with `str::to_lowercase`.
+    pub fn case_insensitive(mut self) -> Self {
+        self.case_insensitive = true;
+        self.words = self
+            .words
+            .into_iter()
+            .map(|(word, keyword)| (word.to_lowercase(), keyword))
+            .collect();
+        self
+    }
+
+    /// Adds `word`, retyped as `token_type` and `token_sub_type`.
+    pub fn insert(&mut self, word: &str, token_type: impl Into<K>, token_sub_type: Option<&str>) {
+        self.insert_keyword(word, token_type.into(), token_sub_type, None);
+    }
+
+    /// Adds `word` as a contextual keyword: it is only retyped when
+    /// `condition` holds, and keeps the type given by the rule otherwise. The
+    /// context is the one the rule ran with, positioned at the start of the
+    /// word.
+    ///
+    /// ```
+    /// use rb_tokenizer::rules::KeywordTable;
+    /// use rb_tokenizer::Tokenizer;
+    ///
+    /// // `async` is only a keyword before `fn`
+    /// let mut tokenizer = Tokenizer::new();
+    /// tokenizer
+    ///     .add_regex_rule(r"[a-z]+", "Identifier", None)
+    ///     .keywords(KeywordTable::new().with("fn", "Keyword", None).with_contextual(
+    ///         "async",
+    ///         "Keyword",
+    ///         None,
+    ///         |context| context.source()[context.offset() + 5..].trim_start().starts_with("fn"),
+    ///     ));
+    ///
+    /// let tokens = tokenizer.tokenize("async fn async").unwrap();
+    /// let types: Vec<_> = tokens.iter().map(|t| t.token_type.as_str()).collect();
+    /// assert_eq!(types, vec!["Keyword", "Keyword", "Identifier"]);
+    /// ```
+    pub fn insert_contextual<F>(
+        &mut self,
+        word: &str,
+        token_type: impl Into<K>,
+        token_sub_type: Option<&str>,
+        condition: F,
+    ) where
+        F: Fn(&mut RuleContext<'_, '_, K>) -> bool + Send + Sync + 'static,
+    {
+        let condition: Arc<KeywordPredicate<K>> = Arc::new(condition);
+        self.insert_keyword(word, token_type.into(), token_sub_type, Some(condition));
+    }
+
+    /// Same as `insert`, for building a table in one expression.
+    pub fn with(
+        mut self,
+        word: &str,
+        token_type: impl Into<K>,
+        token_sub_type: Option<&str>,
+    ) -> Self {
+        self.insert(word, token_type, token_sub_type);
+        self
+    }
+
+    /// Same as `insert_contextual`, for building a table in one expression.
+    pub fn with_contextual<F>(
+        mut self,
+        word: &str,
+        token_type: impl Into<K>,
+        token_sub_type: Option<&str>,
+        condition: F,
+    ) -> Self
+    where
+        F: Fn(&mut RuleContext<'_, '_, K>) -> bool + Send + Sync + 'static,
+    {
+        self.insert_contextual(word, token_type, token_sub_type, condition);
+        self
+    }
+
+    fn insert_keyword(
+        &mut self,
+        word: &str,
+        token_type: K,
+        token_sub_type: Option<&str>,
+        condition: Option<Arc<KeywordPredicate<K>>>,
+    ) {
+        let keyword = Keyword {
+            token_type,
+            token_sub_type: token_sub_type.map(|s| s.to_string()),
+            condition,
+        };
+        self.words.insert(self.key(word).into_owned(), keyword);
+    }
+
+    fn key<'w>(&self, word: &'w str) -> Cow<'w, str> {
+        if self.case_insensitive {
+            Cow::Owned(word.to_lowercase())
+        } else {
+            Cow::Borrowed(word)
+        }
+    }
+
+    /// Returns the keyword `word` is in `context`, if any.
+    pub fn classify(
+        &self,
+        word: &str,
+        context: &mut RuleContext<'_, '_, K>,
+    ) -> Option<&Keyword<K>> {
+        let keyword = self.words.get(self.key(word).as_ref())?;
+        match &keyword.condition {
+            Some(condition) if !condition(context) => None,
+            _ => Some(keyword),
+        }
+    }
+
+    /// Retypes `token` if its value is a keyword in `context`.
+    pub(crate) fn retype<'a>(
+        &'a self,
+        token: &mut BorrowedToken<'a, K>,
+        context: &mut RuleContext<'_, '_, K>,
+    ) {
+        if let Some(keyword) = self.classify(&token.value, context) {
+            token.token_type = Cow::Borrowed(&keyword.token_type);
+            token.token_sub_type = keyword.token_sub_type.as_deref().map(Cow::Borrowed);
+        }
+    }
+}
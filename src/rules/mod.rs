@@ -1,13 +1,27 @@
+pub mod first_bytes;
+pub mod keyword_table;
 pub mod regex_rule;
 pub mod rule;
+pub mod rule_context;
+pub mod rule_match;
 
 pub mod closure_rule;
+pub mod comment_rule;
 pub mod rule_types;
+pub mod string_rule;
 pub mod symbol_rule;
+pub mod symbol_table_rule;
 
-pub use closure_rule::ClosureRule;
+pub use closure_rule::{ClosureFn, ClosureRule};
+pub use comment_rule::{BlockCommentRule, LineCommentRule, COMMENT_TOKEN_TYPE};
+pub use first_bytes::FirstBytes;
+pub use keyword_table::{Keyword, KeywordPredicate, KeywordTable};
 pub use regex_rule::RegexRule;
 pub use rule::Rule;
+pub use rule_context::RuleContext;
+pub use rule_match::Match;
 pub use rule_types::CallbackRule;
 pub use rule_types::RuleType;
+pub use string_rule::StringRule;
 pub use symbol_rule::SymbolRule;
+pub use symbol_table_rule::SymbolTableRule;
@@ -1,37 +1,264 @@
-use super::Rule;
+use super::{FirstBytes, Match, Rule, RuleContext};
+use crate::tokens::BorrowedToken;
+use crate::tokens::Capture;
+use crate::tokens::GrammarError;
+use crate::tokens::Span;
 use crate::tokens::Token;
+use crate::tokens::TokenKind;
 use crate::tokens::TokenizationError;
 
 use regex::Regex;
+use regex_syntax::hir::{Hir, Look};
 
-pub struct RegexRule {
-    pub pattern: Regex,
-    pub token_type: String,
+/// `RegexRule` matches a regular expression at the current position.
+///
+/// Patterns are always anchored, whether or not they start with `^`, and must
+/// not match the empty string.
+///
+/// The compiled pattern is read with `pattern`. Build a new rule to change it.
+#[derive(Clone)]
+pub struct RegexRule<K = String> {
+    pattern: Regex,
+    pub token_type: K,
     pub token_sub_type: Option<String>,
+    source: String,
+    first_bytes: FirstBytes,
+    group_subtypes: bool,
+    captures: bool,
 }
 
-impl RegexRule {
-    pub fn new(pattern: &str, token_type: &str, token_sub_type: Option<&str>) -> Self {
-        Self {
-            pattern: Regex::new(pattern).unwrap(),
-            token_type: token_type.to_string(),
+// What a successful match produces besides the token's type
+struct Found<'a> {
+    mat: regex::Match<'a>,
+    sub_type: Option<&'a str>,
+    captures: Vec<Capture>,
+}
+
+impl<K: TokenKind> RegexRule<K> {
+    /// Creates a rule matching `pattern`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pattern` is not a valid regular expression or can match the
+    /// empty string. Use `try_new` for patterns that are not known in advance.
+    pub fn new(pattern: &str, token_type: impl Into<K>, token_sub_type: Option<&str>) -> Self {
+        Self::try_new(pattern, token_type, token_sub_type).unwrap()
+    }
+
+    /// Same as `new`, but returns an error instead of panicking if `pattern`
+    /// is not a valid regular expression or can match the empty string.
+    pub fn try_new(
+        pattern: &str,
+        token_type: impl Into<K>,
+        token_sub_type: Option<&str>,
+    ) -> Result<Self, GrammarError> {
+        let invalid = |error| GrammarError::InvalidRegex {
+            pattern: pattern.to_string(),
+            mode: None,
+            rule_index: None,
+            error,
+        };
+        Regex::new(pattern).map_err(invalid)?;
+
+        // Parsing succeeds since the regex crate accepted the pattern with the
+        // same syntax and defaults
+        let hir = regex_syntax::parse(pattern).expect("pattern already validated");
+        if hir.properties().minimum_len() == Some(0) {
+            return Err(GrammarError::EmptyMatch {
+                pattern: pattern.to_string(),
+                mode: None,
+                rule_index: None,
+            });
+        }
+
+        let first_bytes = FirstBytes::of_hir(&hir);
+
+        // Anchoring the syntax tree rather than the source text keeps flags
+        // such as `(?x)` from swallowing the anchor's closing parenthesis
+        let anchored = Hir::concat(vec![Hir::look(Look::Start), hir]);
+        let regex = Regex::new(&anchored.to_string()).map_err(invalid)?;
+
+        Ok(Self {
+            pattern: regex,
+            token_type: token_type.into(),
             token_sub_type: token_sub_type.map(|s| s.to_string()),
+            source: pattern.to_string(),
+            first_bytes,
+            group_subtypes: false,
+            captures: false,
+        })
+    }
+
+    /// Derives the subtype of each token from the named capture groups of the
+    /// pattern: the name of the first named group that took part in the match
+    /// becomes the subtype. When none did, `token_sub_type` is used.
+    ///
+    /// ```
+    /// use rb_tokenizer::rules::RegexRule;
+    /// use rb_tokenizer::Tokenizer;
+    ///
+    /// let mut tokenizer = Tokenizer::new();
+    /// tokenizer.add_regex(
+    ///     RegexRule::new(
+    ///         r"(?<Hex>0x[0-9a-fA-F]+)|(?<Float>\d+\.\d+)|\d+",
+    ///         "Number",
+    ///         Some("Integer"),
+    ///     )
+    ///     .with_group_subtypes(),
+    /// );
+    ///
+    /// let tokens = tokenizer.tokenize("0xff 1.5 42").unwrap();
+    /// let sub_types: Vec<_> = tokens.iter().map(|t| t.token_sub_type.as_deref()).collect();
+    /// assert_eq!(sub_types, vec![Some("Hex"), Some("Float"), Some("Integer")]);
+    /// ```
+    pub fn with_group_subtypes(mut self) -> Self {
+        self.group_subtypes = true;
+        self
+    }
+
+    /// Attaches the text and span of every capture group that took part in
+    /// the match to the token, where they can be read with `Token::capture`
+    /// and `Token::capture_at`.
+    ///
+    /// ```
+    /// use rb_tokenizer::rules::RegexRule;
+    /// use rb_tokenizer::Tokenizer;
+    ///
+    /// let mut tokenizer = Tokenizer::new();
+    /// tokenizer.add_regex(
+    ///     RegexRule::new(r"\|(?<name>[a-zA-Z]\w*):", "Pipe", None).with_captures(),
+    /// );
+    ///
+    /// let tokens = tokenizer.tokenize("|map:").unwrap();
+    /// assert_eq!(tokens[0].capture("name").unwrap().text, "map");
+    /// ```
+    pub fn with_captures(mut self) -> Self {
+        self.captures = true;
+        self
+    }
+
+    // Matches at the start of `input`. Capture groups are only resolved when
+    // one of the options needs them, since that is slower.
+    fn find<'a>(&'a self, input: &'a str) -> Option<Found<'a>> {
+        if !self.group_subtypes && !self.captures {
+            return Some(Found {
+                mat: self.pattern.find(input)?,
+                sub_type: self.token_sub_type.as_deref(),
+                captures: Vec::new(),
+            });
+        }
+
+        let groups = self.pattern.captures(input)?;
+        let mut found = Found {
+            mat: groups.get(0).unwrap(),
+            sub_type: self.token_sub_type.as_deref(),
+            captures: Vec::new(),
+        };
+
+        if self.group_subtypes {
+            let group = self
+                .pattern
+                .capture_names()
+                .enumerate()
+                .find_map(|(i, name)| name.filter(|_| groups.get(i).is_some()));
+            found.sub_type = group.or(found.sub_type);
+        }
+
+        if self.captures {
+            // Spans are relative to the input, the tokenizer rebases them
+            found.captures = self
+                .pattern
+                .capture_names()
+                .enumerate()
+                .skip(1)
+                .filter_map(|(index, name)| {
+                    let group = groups.get(index)?;
+                    Some(Capture {
+                        index,
+                        name: name.map(|s| s.to_string()),
+                        text: group.as_str().to_string(),
+                        span: Span::from(group.range()),
+                    })
+                })
+                .collect();
         }
+
+        Some(found)
+    }
+
+    /// Builds the match of `len` bytes at the start of `input` that the
+    /// compiled automaton found for this rule. Capture groups still need the
+    /// pattern to run, the rest of the token does not.
+    pub(crate) fn matched<'a>(
+        &'a self,
+        input: &'a str,
+        len: usize,
+    ) -> Option<Match<BorrowedToken<'a, K>>> {
+        if self.group_subtypes || self.captures {
+            return self.find(input).map(|found| self.borrowed_match(found));
+        }
+        let token = BorrowedToken::new(
+            &self.token_type,
+            self.token_sub_type.as_deref(),
+            &input[..len],
+        );
+        Some(Match::new(len, token))
+    }
+
+    fn borrowed_match<'a>(&'a self, found: Found<'a>) -> Match<BorrowedToken<'a, K>> {
+        Match::new(
+            found.mat.end(),
+            BorrowedToken {
+                captures: found.captures,
+                ..BorrowedToken::new(&self.token_type, found.sub_type, found.mat.as_str())
+            },
+        )
+    }
+
+    /// The anchored form of the pattern, see `source` for the original.
+    pub fn pattern(&self) -> &Regex {
+        &self.pattern
+    }
+
+    /// The pattern as it was given, before anchoring.
+    pub fn source(&self) -> &str {
+        &self.source
     }
 }
 
-impl Rule for RegexRule {
-    fn process(&self, input: &str) -> Result<Option<Token>, TokenizationError> {
-        if let Some(mat) = self.pattern.find(input) {
-            Ok(Some(Token {
-                token_type: self.token_type.clone(),
-                value: mat.as_str().to_string(),
-                line: 0,
-                column: 0,
-                token_sub_type: None,
-            }))
+impl<K: TokenKind> Rule<K> for RegexRule<K> {
+    fn process(
+        &self,
+        input: &str,
+        _context: &mut RuleContext<'_, '_, K>,
+    ) -> Result<Option<Match<Token<K>>>, TokenizationError> {
+        if let Some(found) = self.find(input) {
+            Ok(Some(Match::new(
+                found.mat.end(),
+                Token {
+                    token_type: self.token_type.clone(),
+                    value: found.mat.as_str().to_string(),
+                    line: 0,
+                    column: 0,
+                    span: Span::default(),
+                    token_sub_type: found.sub_type.map(|s| s.to_string()),
+                    captures: found.captures,
+                },
+            )))
         } else {
             Ok(None)
         }
     }
+
+    fn process_borrowed<'a>(
+        &'a self,
+        input: &'a str,
+        _context: &mut RuleContext<'_, '_, K>,
+    ) -> Result<Option<Match<BorrowedToken<'a, K>>>, TokenizationError> {
+        Ok(self.find(input).map(|found| self.borrowed_match(found)))
+    }
+
+    fn first_bytes(&self) -> FirstBytes {
+        self.first_bytes
+    }
 }
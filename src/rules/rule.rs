@@ -1,10 +1,48 @@
+use super::{FirstBytes, Match, RuleContext};
+use crate::tokens::BorrowedToken;
 use crate::tokens::Token;
+use crate::tokens::TokenKind;
 use crate::tokens::TokenizationError;
 
 // use regex::Regex;
 
-pub trait Rule {
-    fn process(&self, input: &str) -> Result<Option<Token>, TokenizationError>;
+/// `Rule` recognizes a token at the start of its input.
+///
+/// A rule that matches returns a `Match` telling the tokenizer how many bytes
+/// it consumed and which token, if any, to emit for them. The tokenizer fills
+/// in the token's `line`, `column` and `span` when it is emitted.
+///
+/// `input` is the remaining input, starting at the current position;
+/// `context` describes the rest of the tokenization state.
+///
+/// Rules are `Send + Sync` so that a `Tokenizer` can be shared between
+/// threads; per-run mutable state belongs in `RuleContext::state`.
+pub trait Rule<K: TokenKind = String>: Send + Sync {
+    fn process(
+        &self,
+        input: &str,
+        context: &mut RuleContext<'_, '_, K>,
+    ) -> Result<Option<Match<Token<K>>>, TokenizationError>;
+
+    /// Same as `process`, but returns a token that may borrow from the input and
+    /// from the rule itself. Rules that can match without allocating should
+    /// override this; the default wraps the owned token returned by `process`.
+    fn process_borrowed<'a>(
+        &'a self,
+        input: &'a str,
+        context: &mut RuleContext<'_, '_, K>,
+    ) -> Result<Option<Match<BorrowedToken<'a, K>>>, TokenizationError> {
+        Ok(self
+            .process(input, context)?
+            .map(|m| m.map(BorrowedToken::from)))
+    }
+
+    /// The bytes a match of this rule can start with. The tokenizer only runs
+    /// the rule at positions starting with one of them, so rules that know
+    /// should override this; the default allows every byte.
+    fn first_bytes(&self) -> FirstBytes {
+        FirstBytes::any()
+    }
 }
 
 // struct RegexRule {
@@ -0,0 +1,75 @@
+use std::any::Any;
+
+use crate::tokens::{BorrowedToken, TokenKind};
+
+/// `RuleContext` describes where the tokenizer is when it runs a rule: the
+/// position in the source, the active lexer mode, the tokens emitted so far
+/// and the user state of the tokenization run, if any.
+pub struct RuleContext<'c, 'a, K: TokenKind = String> {
+    pub(crate) source: &'a str,
+    pub(crate) offset: usize,
+    pub(crate) line: usize,
+    pub(crate) column: usize,
+    pub(crate) mode: &'a str,
+    pub(crate) previous: Option<&'c BorrowedToken<'a, K>>,
+    pub(crate) previous_significant: Option<&'c BorrowedToken<'a, K>>,
+    pub(crate) state: Option<&'c mut dyn Any>,
+}
+
+impl<'c, 'a, K: TokenKind> RuleContext<'c, 'a, K> {
+    /// Creates a context at the start of `source`, with no previous tokens and
+    /// no state. Useful to run a rule outside of a tokenizer, such as in tests.
+    pub fn new(source: &'a str) -> Self {
+        RuleContext {
+            source,
+            offset: 0,
+            line: 1,
+            column: 1,
+            mode: crate::tokenizers::DEFAULT_MODE,
+            previous: None,
+            previous_significant: None,
+            state: None,
+        }
+    }
+
+    /// The whole input being tokenized.
+    pub fn source(&self) -> &'a str {
+        self.source
+    }
+
+    /// The byte offset in `source` the rule is matching at.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// The line the rule is matching at.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// The column the rule is matching at.
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    /// The name of the innermost active lexer mode.
+    pub fn mode(&self) -> &'a str {
+        self.mode
+    }
+
+    /// The last token emitted, if any.
+    pub fn previous(&self) -> Option<&'c BorrowedToken<'a, K>> {
+        self.previous
+    }
+
+    /// The last token emitted that is not trivia (see `TokenKind::is_trivia`),
+    /// if any.
+    pub fn previous_significant(&self) -> Option<&'c BorrowedToken<'a, K>> {
+        self.previous_significant
+    }
+
+    /// The user state of the tokenization run, if it has state of type `S`.
+    pub fn state<S: Any>(&mut self) -> Option<&mut S> {
+        self.state.as_deref_mut()?.downcast_mut()
+    }
+}
@@ -0,0 +1,74 @@
+use crate::tokens::{BorrowedToken, Token, TokenKind, TokenizationError};
+
+/// `Match` is what a rule returns when it recognizes the start of its input:
+/// how many bytes of the input it consumed, and the token to emit for them.
+///
+/// `consumed` is independent of the token's `value`, so a rule can emit a
+/// decoded value (a string with its escapes processed, a number without `_`
+/// separators), or consume input without emitting anything by leaving `token`
+/// empty.
+///
+/// A rule that recovers from malformed input, such as an unterminated block
+/// comment, still returns a match and records what went wrong in `errors`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Match<T> {
+    /// The number of bytes consumed from the input. It must be greater than
+    /// zero and fall on a character boundary.
+    pub consumed: usize,
+
+    /// The token to emit, or `None` to skip the consumed input.
+    pub token: Option<T>,
+
+    /// Errors to report along with the match. Their spans are relative to the
+    /// rule's input, like those of the errors rules return.
+    pub errors: Vec<TokenizationError>,
+}
+
+impl<T> Match<T> {
+    /// Creates a match that consumes `consumed` bytes and emits `token`.
+    pub fn new(consumed: usize, token: T) -> Self {
+        Match {
+            consumed,
+            token: Some(token),
+            errors: Vec::new(),
+        }
+    }
+
+    /// Creates a match that consumes `consumed` bytes without emitting a token.
+    pub fn skip(consumed: usize) -> Self {
+        Match {
+            consumed,
+            token: None,
+            errors: Vec::new(),
+        }
+    }
+
+    /// Reports `error` along with the match.
+    pub fn with_error(mut self, error: TokenizationError) -> Self {
+        self.errors.push(error);
+        self
+    }
+
+    /// Converts the token of the match, if any.
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> Match<U> {
+        Match {
+            consumed: self.consumed,
+            token: self.token.map(f),
+            errors: self.errors,
+        }
+    }
+}
+
+/// A token converts into a match that consumes exactly its value.
+impl<K> From<Token<K>> for Match<Token<K>> {
+    fn from(token: Token<K>) -> Self {
+        Match::new(token.value.len(), token)
+    }
+}
+
+/// A token converts into a match that consumes exactly its value.
+impl<'a, K: TokenKind> From<BorrowedToken<'a, K>> for Match<BorrowedToken<'a, K>> {
+    fn from(token: BorrowedToken<'a, K>) -> Self {
+        Match::new(token.value.len(), token)
+    }
+}
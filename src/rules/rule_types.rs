@@ -1,29 +1,71 @@
-use crate::tokens::{Token, TokenizationError};
+use std::sync::Arc;
+
+use crate::tokens::{BorrowedToken, Token, TokenKind, TokenizationError};
 
 use super::regex_rule::RegexRule;
 use super::symbol_rule::SymbolRule;
-use super::{ClosureRule, Rule};
+use super::symbol_table_rule::SymbolTableRule;
+use super::{ClosureRule, FirstBytes, Match, Rule, RuleContext};
 
-pub enum RuleType {
-    Symbol(SymbolRule),
-    Regex(RegexRule),
-    Closure(ClosureRule),
-    Rule(Box<dyn Rule>),
-    Callback(Box<dyn CallbackRule>),
+#[derive(Clone)]
+pub enum RuleType<K: TokenKind = String> {
+    Symbol(SymbolRule<K>),
+    SymbolTable(SymbolTableRule<K>),
+    Regex(RegexRule<K>),
+    Closure(ClosureRule<K>),
+    Rule(Arc<dyn Rule<K>>),
+    Callback(Arc<dyn CallbackRule<K>>),
 }
 
-pub trait CallbackRule {
-    fn process(&self, input: &str) -> Result<Option<Token>, TokenizationError>;
+pub trait CallbackRule<K: TokenKind = String>: Send + Sync {
+    fn process(
+        &self,
+        input: &str,
+        context: &mut RuleContext<'_, '_, K>,
+    ) -> Result<Option<Match<Token<K>>>, TokenizationError>;
 }
 
-impl Rule for RuleType {
-    fn process(&self, input: &str) -> Result<Option<Token>, TokenizationError> {
+impl<K: TokenKind> Rule<K> for RuleType<K> {
+    fn process(
+        &self,
+        input: &str,
+        context: &mut RuleContext<'_, '_, K>,
+    ) -> Result<Option<Match<Token<K>>>, TokenizationError> {
+        match self {
+            RuleType::Symbol(rule) => rule.process(input, context),
+            RuleType::SymbolTable(rule) => rule.process(input, context),
+            RuleType::Regex(rule) => rule.process(input, context),
+            RuleType::Closure(rule) => rule.process(input, context),
+            RuleType::Rule(rule) => rule.process(input, context),
+            RuleType::Callback(rule) => rule.process(input, context),
+        }
+    }
+
+    fn process_borrowed<'a>(
+        &'a self,
+        input: &'a str,
+        context: &mut RuleContext<'_, '_, K>,
+    ) -> Result<Option<Match<BorrowedToken<'a, K>>>, TokenizationError> {
+        match self {
+            RuleType::Symbol(rule) => rule.process_borrowed(input, context),
+            RuleType::SymbolTable(rule) => rule.process_borrowed(input, context),
+            RuleType::Regex(rule) => rule.process_borrowed(input, context),
+            RuleType::Closure(rule) => rule.process_borrowed(input, context),
+            RuleType::Rule(rule) => rule.process_borrowed(input, context),
+            RuleType::Callback(rule) => Ok(rule
+                .process(input, context)?
+                .map(|m| m.map(BorrowedToken::from))),
+        }
+    }
+
+    fn first_bytes(&self) -> FirstBytes {
         match self {
-            RuleType::Symbol(rule) => rule.process(input),
-            RuleType::Regex(rule) => rule.process(input),
-            RuleType::Closure(rule) => rule.process(input),
-            RuleType::Rule(rule) => rule.process(input),
-            RuleType::Callback(rule) => rule.process(input),
+            RuleType::Symbol(rule) => rule.first_bytes(),
+            RuleType::SymbolTable(rule) => rule.first_bytes(),
+            RuleType::Regex(rule) => rule.first_bytes(),
+            RuleType::Rule(rule) => rule.first_bytes(),
+            // Closures and callbacks are opaque
+            RuleType::Closure(_) | RuleType::Callback(_) => FirstBytes::any(),
         }
     }
 }
@@ -0,0 +1,305 @@
+use std::borrow::Cow;
+
+use super::{FirstBytes, Match, Rule, RuleContext};
+use crate::tokens::BorrowedToken;
+use crate::tokens::Span;
+use crate::tokens::Token;
+use crate::tokens::TokenKind;
+use crate::tokens::TokenizationError;
+
+/// `StringRule` matches a string literal delimited by one of its quote
+/// characters, and emits its contents with the escape sequences decoded as
+/// the token's value. The raw text, quotes included, is still available
+/// through the token's span (see `Token::text`).
+///
+/// By default strings are delimited by `"`, cannot span lines and support the
+/// escapes `\n`, `\t`, `\r`, `\0`, `\\`, `\"` and `\'`, as well as `\xNN`,
+/// `\uXXXX` (combining UTF-16 surrogate pairs) and `\u{X...}`. An escaped
+/// quote character or escape character always stands for itself.
+///
+/// Malformed strings are still matched so that tokenization can go on, and
+/// reported along with the match:
+///
+/// - an invalid escape sequence is kept as written in the value and reported
+///   as `TokenizationError::InvalidEscape`, located at the sequence;
+/// - a string that is not closed by the end of the line (or of the input, for
+///   multiline strings) ends there and is reported as
+///   `TokenizationError::Unterminated`, located at the opening quote. An
+///   escape character right before the line break or the end of the input is
+///   consumed with the string but left out of its value.
+///
+/// In multiline strings, an escape character followed by a line break is a
+/// line continuation: neither of them is part of the value.
+///
+/// ```
+/// use rb_tokenizer::rules::StringRule;
+/// use rb_tokenizer::Tokenizer;
+///
+/// let mut tokenizer = Tokenizer::new();
+/// tokenizer.add_rule(Box::new(StringRule::new("String", None).quotes(&['"', '\''])));
+///
+/// let input = r#""tab\there" 'caf\u{e9}'"#;
+/// let tokens = tokenizer.tokenize(input).unwrap();
+/// assert_eq!(tokens[0].value, "tab\there");
+/// assert_eq!(tokens[0].text(input), r#""tab\there""#);
+/// assert_eq!(tokens[1].value, "café");
+/// ```
+#[derive(Clone)]
+pub struct StringRule<K = String> {
+    pub token_type: K,
+    pub token_sub_type: Option<String>,
+    quotes: Vec<char>,
+    // `None` in raw mode
+    escape: Option<char>,
+    // Single character escapes and what they stand for
+    escapes: Vec<(char, char)>,
+    multiline: bool,
+}
+
+// A string literal found at the start of the input
+struct Found<'a> {
+    len: usize,
+    value: Cow<'a, str>,
+    errors: Vec<TokenizationError>,
+}
+
+impl<K: TokenKind> StringRule<K> {
+    pub fn new(token_type: impl Into<K>, token_sub_type: Option<&str>) -> Self {
+        StringRule {
+            token_type: token_type.into(),
+            token_sub_type: token_sub_type.map(|s| s.to_string()),
+            quotes: vec!['"'],
+            escape: Some('\\'),
+            escapes: vec![
+                ('n', '\n'),
+                ('t', '\t'),
+                ('r', '\r'),
+                ('0', '\0'),
+                ('"', '"'),
+                ('\'', '\''),
+            ],
+            multiline: false,
+        }
+    }
+
+    /// Sets the characters that can delimit a string. A string ends with the
+    /// quote it started with.
+    pub fn quotes(mut self, quotes: &[char]) -> Self {
+        self.quotes = quotes.to_vec();
+        self
+    }
+
+    /// Sets the character that starts escape sequences, `\` by default.
+    pub fn escape_char(mut self, escape: char) -> Self {
+        self.escape = Some(escape);
+        self
+    }
+
+    /// Adds the escape sequence made of the escape character followed by
+    /// `c`, standing for `value`, or changes what it stands for.
+    pub fn with_escape(mut self, c: char, value: char) -> Self {
+        self.escapes.retain(|(escaped, _)| *escaped != c);
+        self.escapes.push((c, value));
+        self
+    }
+
+    /// Disables escape sequences: the value is the text between the quotes
+    /// as written.
+    pub fn raw(mut self) -> Self {
+        self.escape = None;
+        self
+    }
+
+    /// Allows strings to span lines.
+    pub fn multiline(mut self) -> Self {
+        self.multiline = true;
+        self
+    }
+
+    fn find<'a>(&self, input: &'a str) -> Option<Found<'a>> {
+        let quote = input.chars().next().filter(|c| self.quotes.contains(c))?;
+        let start = quote.len_utf8();
+
+        // Allocated at the first escape sequence only
+        let mut decoded: Option<String> = None;
+        let mut copied = start;
+        let mut errors = Vec::new();
+        let mut offset = start;
+        // An escape character ending an unterminated string, consumed with it
+        // but left out of the value
+        let mut trailing = 0;
+
+        let closed = loop {
+            let rest = &input[offset..];
+            let Some(c) = rest.chars().next() else {
+                break false;
+            };
+            if c == quote {
+                break true;
+            }
+            if !self.multiline && (c == '\n' || rest.starts_with("\r\n")) {
+                break false;
+            }
+            if Some(c) != self.escape {
+                offset += c.len_utf8();
+                continue;
+            }
+
+            let sequence = &rest[c.len_utf8()..];
+            if sequence.is_empty() {
+                // The escape character ends the input
+                trailing = c.len_utf8();
+                break false;
+            }
+            let line_break = ["\n", "\r\n"]
+                .into_iter()
+                .find(|line_break| sequence.starts_with(line_break));
+            if line_break.is_some() && !self.multiline {
+                // The string ends at the line break
+                trailing = c.len_utf8();
+                break false;
+            }
+            let value = decoded.get_or_insert_with(String::new);
+            value.push_str(&input[copied..offset]);
+            if let Some(line_break) = line_break {
+                // A line continuation, which leaves nothing in the value
+                offset += c.len_utf8() + line_break.len();
+                copied = offset;
+                continue;
+            }
+            let (escaped, len) = self.decode(c, sequence);
+            let len = c.len_utf8() + len;
+            match escaped {
+                Some(escaped) => value.push(escaped),
+                None => {
+                    let sequence = &rest[..len];
+                    value.push_str(sequence);
+                    let span = Span::new(offset, offset + len);
+                    errors.push(TokenizationError::invalid_escape(sequence, span));
+                }
+            }
+            offset += len;
+            copied = offset;
+        };
+
+        let value = match decoded {
+            Some(mut value) => {
+                value.push_str(&input[copied..offset]);
+                Cow::Owned(value)
+            }
+            None => Cow::Borrowed(&input[start..offset]),
+        };
+        let len = if closed {
+            offset + quote.len_utf8()
+        } else {
+            let span = Span::new(0, start);
+            errors.push(TokenizationError::unterminated("string", span));
+            offset + trailing
+        };
+
+        Some(Found { len, value, errors })
+    }
+
+    // Decodes the escape sequence following the escape character `escape`,
+    // returning the character it stands for, if valid, and its length
+    fn decode(&self, escape: char, sequence: &str) -> (Option<char>, usize) {
+        let c = sequence.chars().next().unwrap();
+        if c == escape || self.quotes.contains(&c) {
+            return (Some(c), c.len_utf8());
+        }
+        if let Some((_, value)) = self.escapes.iter().find(|(escaped, _)| *escaped == c) {
+            return (Some(*value), c.len_utf8());
+        }
+
+        match c {
+            'x' => {
+                let (code, len) = hex(&sequence[1..], 2, 2);
+                (code.and_then(char::from_u32), 1 + len)
+            }
+            'u' if sequence[1..].starts_with('{') => {
+                let digits = &sequence[1..];
+                let (code, len) = hex(&digits[1..], 1, 6);
+                if code.is_some() && digits[1 + len..].starts_with('}') {
+                    (code.and_then(char::from_u32), 3 + len)
+                } else {
+                    (None, 2 + len)
+                }
+            }
+            'u' => {
+                let digits = &sequence[1..];
+                let (code, len) = hex(digits, 4, 4);
+                match code {
+                    Some(high @ 0xD800..=0xDBFF) => {
+                        // A high surrogate needs to be followed by `\u` and a
+                        // low surrogate
+                        let low = digits[4..]
+                            .strip_prefix(escape)
+                            .and_then(|rest| rest.strip_prefix('u'))
+                            .map(|rest| hex(rest, 4, 4));
+                        match low {
+                            Some((Some(low @ 0xDC00..=0xDFFF), _)) => {
+                                let code = 0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00);
+                                (char::from_u32(code), 5 + escape.len_utf8() + 5)
+                            }
+                            _ => (None, 5),
+                        }
+                    }
+                    _ => (code.and_then(char::from_u32), 1 + len),
+                }
+            }
+            _ => (None, c.len_utf8()),
+        }
+    }
+}
+
+// Parses `min` to `max` hex digits at the start of `text`, returning their
+// value if there are at least `min` and the number of digits read
+fn hex(text: &str, min: usize, max: usize) -> (Option<u32>, usize) {
+    let len = text
+        .bytes()
+        .take(max)
+        .take_while(u8::is_ascii_hexdigit)
+        .count();
+    if len < min {
+        return (None, len);
+    }
+    (u32::from_str_radix(&text[..len], 16).ok(), len)
+}
+
+impl<K: TokenKind> Rule<K> for StringRule<K> {
+    fn process(
+        &self,
+        input: &str,
+        context: &mut RuleContext<'_, '_, K>,
+    ) -> Result<Option<Match<Token<K>>>, TokenizationError> {
+        Ok(self
+            .process_borrowed(input, context)?
+            .map(|m| m.map(Token::from)))
+    }
+
+    fn process_borrowed<'a>(
+        &'a self,
+        input: &'a str,
+        _context: &mut RuleContext<'_, '_, K>,
+    ) -> Result<Option<Match<BorrowedToken<'a, K>>>, TokenizationError> {
+        let Some(found) = self.find(input) else {
+            return Ok(None);
+        };
+
+        let token = BorrowedToken {
+            value: found.value,
+            ..BorrowedToken::new(&self.token_type, self.token_sub_type.as_deref(), "")
+        };
+        let mut m = Match::new(found.len, token);
+        m.errors = found.errors;
+        Ok(Some(m))
+    }
+
+    fn first_bytes(&self) -> FirstBytes {
+        let mut first_bytes = FirstBytes::none();
+        for quote in &self.quotes {
+            first_bytes = first_bytes.union(FirstBytes::of_str(quote.encode_utf8(&mut [0; 4])));
+        }
+        first_bytes
+    }
+}
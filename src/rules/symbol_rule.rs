@@ -1,35 +1,72 @@
 use super::rule::Rule;
+use super::{FirstBytes, Match, RuleContext};
+use crate::tokens::BorrowedToken;
+use crate::tokens::Span;
 use crate::tokens::Token;
+use crate::tokens::TokenKind;
 use crate::tokens::TokenizationError;
 
-pub struct SymbolRule {
+#[derive(Clone)]
+pub struct SymbolRule<K = String> {
     pub symbol: String,
-    pub token_type: String,
+    pub token_type: K,
     pub token_sub_type: Option<String>,
 }
 
-impl SymbolRule {
-    pub fn new(symbol: &str, token_type: &str, token_sub_type: Option<&str>) -> Self {
+impl<K: TokenKind> SymbolRule<K> {
+    pub fn new(symbol: &str, token_type: impl Into<K>, token_sub_type: Option<&str>) -> Self {
         Self {
             symbol: symbol.to_string(),
-            token_type: token_type.to_string(),
+            token_type: token_type.into(),
             token_sub_type: token_sub_type.map(|s| s.to_string()),
         }
     }
 }
 
-impl Rule for SymbolRule {
-    fn process(&self, input: &str) -> Result<Option<Token>, TokenizationError> {
+impl<K: TokenKind> Rule<K> for SymbolRule<K> {
+    fn process(
+        &self,
+        input: &str,
+        _context: &mut RuleContext<'_, '_, K>,
+    ) -> Result<Option<Match<Token<K>>>, TokenizationError> {
         if input.starts_with(&self.symbol) {
-            Ok(Some(Token {
-                line: 0,
-                column: 0,
-                value: self.symbol.clone(),
-                token_type: self.token_type.clone(),
-                token_sub_type: self.token_sub_type.clone(),
-            }))
+            Ok(Some(Match::new(
+                self.symbol.len(),
+                Token {
+                    line: 0,
+                    column: 0,
+                    span: Span::default(),
+                    value: self.symbol.clone(),
+                    token_type: self.token_type.clone(),
+                    token_sub_type: self.token_sub_type.clone(),
+                    captures: Vec::new(),
+                },
+            )))
         } else {
             Ok(None)
         }
     }
+
+    fn process_borrowed<'a>(
+        &'a self,
+        input: &'a str,
+        _context: &mut RuleContext<'_, '_, K>,
+    ) -> Result<Option<Match<BorrowedToken<'a, K>>>, TokenizationError> {
+        if input.starts_with(&self.symbol) {
+            Ok(Some(Match::new(
+                self.symbol.len(),
+                BorrowedToken::new(
+                    &self.token_type,
+                    self.token_sub_type.as_deref(),
+                    &input[..self.symbol.len()],
+                ),
+            )))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn first_bytes(&self) -> FirstBytes {
+        FirstBytes::of_str(&self.symbol)
+    }
 }
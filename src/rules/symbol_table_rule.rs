@@ -0,0 +1,154 @@
+use super::{FirstBytes, Match, Rule, RuleContext, SymbolRule};
+use crate::tokens::BorrowedToken;
+use crate::tokens::Token;
+use crate::tokens::TokenKind;
+use crate::tokens::TokenizationError;
+
+/// `SymbolTableRule` matches the longest of a set of symbols at the current
+/// position. The symbols are stored in a trie, so the input is read once
+/// however many symbols there are, and the order they were added in does not
+/// matter: `<=` wins over `<` either way.
+///
+/// Adding a symbol that is already in the table replaces its token type and
+/// subtype. Empty symbols are ignored since they would not consume any input.
+#[derive(Clone)]
+pub struct SymbolTableRule<K = String> {
+    symbols: Vec<SymbolRule<K>>,
+    // The root is always the first node
+    nodes: Vec<Node>,
+}
+
+#[derive(Clone, Default)]
+struct Node {
+    // Sorted by byte
+    children: Vec<(u8, usize)>,
+    // The symbol ending at this node, as an index into `symbols`
+    symbol: Option<usize>,
+}
+
+impl<K: TokenKind> Default for SymbolTableRule<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: TokenKind> SymbolTableRule<K> {
+    pub fn new() -> Self {
+        SymbolTableRule {
+            symbols: Vec::new(),
+            nodes: vec![Node::default()],
+        }
+    }
+
+    /// Adds `symbol`, emitted as a token of type `token_type`.
+    pub fn insert(&mut self, symbol: &str, token_type: impl Into<K>, token_sub_type: Option<&str>) {
+        if symbol.is_empty() {
+            return;
+        }
+
+        let mut node = 0;
+        for &byte in symbol.as_bytes() {
+            node = match self.nodes[node]
+                .children
+                .binary_search_by_key(&byte, |c| c.0)
+            {
+                Ok(child) => self.nodes[node].children[child].1,
+                Err(position) => {
+                    self.nodes.push(Node::default());
+                    let child = self.nodes.len() - 1;
+                    self.nodes[node].children.insert(position, (byte, child));
+                    child
+                }
+            };
+        }
+
+        let rule = SymbolRule::new(symbol, token_type, token_sub_type);
+        match self.nodes[node].symbol {
+            Some(index) => self.symbols[index] = rule,
+            None => {
+                self.symbols.push(rule);
+                self.nodes[node].symbol = Some(self.symbols.len() - 1);
+            }
+        }
+    }
+
+    /// Same as `insert`, for building a table in one expression.
+    pub fn with(
+        mut self,
+        symbol: &str,
+        token_type: impl Into<K>,
+        token_sub_type: Option<&str>,
+    ) -> Self {
+        self.insert(symbol, token_type, token_sub_type);
+        self
+    }
+
+    /// The symbols of the table, in the order they were first added.
+    pub fn symbols(&self) -> &[SymbolRule<K>] {
+        &self.symbols
+    }
+
+    /// Returns the longest symbol `input` starts with.
+    pub fn longest_match(&self, input: &str) -> Option<&SymbolRule<K>> {
+        let mut node = 0;
+        let mut longest = None;
+
+        for &byte in input.as_bytes() {
+            let children = &self.nodes[node].children;
+            match children.binary_search_by_key(&byte, |c| c.0) {
+                Ok(child) => node = children[child].1,
+                Err(_) => break,
+            }
+            if let Some(index) = self.nodes[node].symbol {
+                longest = Some(&self.symbols[index]);
+            }
+        }
+
+        longest
+    }
+
+    /// A regular expression matching the same symbols, or `None` if the table
+    /// is empty. Longer symbols come first, so that leftmost-first matching
+    /// picks the longest one like `longest_match`.
+    pub(crate) fn pattern(&self) -> Option<String> {
+        if self.symbols.is_empty() {
+            return None;
+        }
+        let mut symbols: Vec<&str> = self
+            .symbols
+            .iter()
+            .map(|rule| rule.symbol.as_str())
+            .collect();
+        symbols.sort_by_key(|symbol| std::cmp::Reverse(symbol.len()));
+        let alternatives: Vec<String> = symbols.into_iter().map(regex::escape).collect();
+        Some(alternatives.join("|"))
+    }
+}
+
+impl<K: TokenKind> Rule<K> for SymbolTableRule<K> {
+    fn process(
+        &self,
+        input: &str,
+        context: &mut RuleContext<'_, '_, K>,
+    ) -> Result<Option<Match<Token<K>>>, TokenizationError> {
+        match self.longest_match(input) {
+            Some(rule) => rule.process(input, context),
+            None => Ok(None),
+        }
+    }
+
+    fn process_borrowed<'a>(
+        &'a self,
+        input: &'a str,
+        context: &mut RuleContext<'_, '_, K>,
+    ) -> Result<Option<Match<BorrowedToken<'a, K>>>, TokenizationError> {
+        match self.longest_match(input) {
+            Some(rule) => rule.process_borrowed(input, context),
+            None => Ok(None),
+        }
+    }
+
+    fn first_bytes(&self) -> FirstBytes {
+        self.nodes[0].children.iter().map(|c| c.0).collect()
+    }
+}
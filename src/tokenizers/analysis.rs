@@ -0,0 +1,378 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+
+use regex::Regex;
+use regex_automata::dfa::{dense, Automaton, StartKind};
+use regex_automata::util::primitives::StateID;
+use regex_automata::util::start;
+use regex_automata::{Anchored, MatchKind};
+use regex_syntax::hir::literal::Extractor;
+use regex_syntax::hir::Look;
+
+use super::mode::RuleEntry;
+use super::MatchStrategy;
+use crate::rules::RuleType;
+use crate::tokens::TokenKind;
+
+/// `GrammarIssue` is a problem found in a grammar by `Tokenizer::analyze`.
+/// Rules are identified by the mode they belong to and their index among the
+/// rules added to that mode, the same index `GrammarError`s report.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GrammarIssue {
+    /// The rule `rule` can never produce a token: wherever it matches, the
+    /// earlier rule `by` matches as well and wins under the match strategy.
+    Shadowed {
+        mode: String,
+        rule: usize,
+        by: usize,
+    },
+
+    /// The rules `first` and `second` can both match at the same position,
+    /// for instance at the start of `example`. Which one wins depends on the
+    /// match strategy and on the order of the rules.
+    Overlap {
+        mode: String,
+        first: usize,
+        second: usize,
+        example: String,
+    },
+
+    /// The rule `rule` can match the empty string.
+    EmptyMatch { mode: String, rule: usize },
+}
+
+impl fmt::Display for GrammarIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GrammarIssue::Shadowed { mode, rule, by } => {
+                write!(
+                    f,
+                    "Rule #{} in mode {} is shadowed by rule #{}",
+                    rule, mode, by
+                )
+            }
+            GrammarIssue::Overlap {
+                mode,
+                first,
+                second,
+                example,
+            } => write!(
+                f,
+                "Rules #{} and #{} in mode {} both match {:?}",
+                first, second, mode, example
+            ),
+            GrammarIssue::EmptyMatch { mode, rule } => {
+                write!(
+                    f,
+                    "Rule #{} in mode {} can match the empty string",
+                    rule, mode
+                )
+            }
+        }
+    }
+}
+
+// Give up on a pair of rules rather than explore more states than this
+const MAX_EXPLORED_STATES: usize = 20_000;
+
+/// Analyzes the symbol and regex rules of one mode. Closure, callback and
+/// custom rules are opaque and are skipped.
+pub(crate) fn analyze_mode<K: TokenKind>(
+    mode: &str,
+    rules: &[RuleEntry<K>],
+    strategy: MatchStrategy,
+) -> Vec<GrammarIssue> {
+    let mut issues = Vec::new();
+    let mut languages: Vec<(usize, Language)> = Vec::new();
+
+    for entry in rules {
+        let index = entry.index;
+        let Some(language) = Language::new(&entry.rule) else {
+            continue;
+        };
+        if language.matches_empty {
+            issues.push(GrammarIssue::EmptyMatch {
+                mode: mode.to_string(),
+                rule: index,
+            });
+            continue;
+        }
+
+        let mut overlaps = Vec::new();
+        let mut shadowed_by = None;
+        for (earlier, other) in &languages {
+            let relation = other.relation(&language, strategy);
+            if relation.shadowed {
+                shadowed_by = Some(*earlier);
+                break;
+            }
+            if let Some(example) = relation.overlap {
+                overlaps.push(GrammarIssue::Overlap {
+                    mode: mode.to_string(),
+                    first: *earlier,
+                    second: index,
+                    example,
+                });
+            }
+        }
+
+        match shadowed_by {
+            Some(by) => issues.push(GrammarIssue::Shadowed {
+                mode: mode.to_string(),
+                rule: index,
+                by,
+            }),
+            None => issues.extend(overlaps),
+        }
+        languages.push((index, language));
+    }
+
+    issues
+}
+
+// How a later rule relates to an earlier one
+struct Relation {
+    shadowed: bool,
+    overlap: Option<String>,
+}
+
+// The set of inputs a rule matches at the start of
+struct Language {
+    // The anchored pattern of the rule
+    regex: Regex,
+    // Every string the rule can match, if there are finitely many
+    literals: Option<Vec<String>>,
+    // `None` if the pattern is not supported by DFAs or the DFA is too large
+    dfa: Option<dense::DFA<Vec<u32>>>,
+    matches_empty: bool,
+    // Whether the pattern has look-around assertions, whose outcome depends
+    // on the input around a match and not only on the match itself
+    looks: bool,
+}
+
+impl Language {
+    fn new<K: TokenKind>(rule: &RuleType<K>) -> Option<Self> {
+        let (source, regex) = match rule {
+            RuleType::Symbol(rule) => {
+                let source = regex::escape(&rule.symbol);
+                let regex = Regex::new(&format!("^{}", source)).ok()?;
+                (source, regex)
+            }
+            RuleType::SymbolTable(rule) => {
+                let source = rule.pattern()?;
+                let regex = Regex::new(&format!("^(?:{})", source)).ok()?;
+                (source, regex)
+            }
+            RuleType::Regex(rule) => (rule.source().to_string(), rule.pattern().clone()),
+            _ => return None,
+        };
+
+        let hir = regex_syntax::parse(&source).ok()?;
+        let literals = Extractor::new()
+            .extract(&hir)
+            .literals()
+            .filter(|literals| literals.iter().all(|literal| literal.is_exact()))
+            .and_then(|literals| {
+                literals
+                    .iter()
+                    .map(|literal| String::from_utf8(literal.as_bytes().to_vec()).ok())
+                    .collect()
+            });
+
+        let dfa = dense::Builder::new()
+            .configure(
+                dense::Config::new()
+                    .match_kind(MatchKind::All)
+                    .start_kind(StartKind::Anchored)
+                    .unicode_word_boundary(true)
+                    .dfa_size_limit(Some(1 << 20))
+                    .determinize_size_limit(Some(1 << 20)),
+            )
+            .build(&source)
+            .ok();
+
+        Some(Language {
+            regex,
+            literals,
+            dfa,
+            matches_empty: hir.properties().minimum_len() == Some(0),
+            // Rules are anchored anyway, so a leading `^` changes nothing
+            looks: !hir.properties().look_set().remove(Look::Start).is_empty(),
+        })
+    }
+
+    // Relates `later`, a rule registered after this one, to this rule
+    fn relation(&self, later: &Language, strategy: MatchStrategy) -> Relation {
+        if let (Some(first), Some(second)) = (&self.dfa, &later.dfa) {
+            if let Some(product) = explore(first, second) {
+                let shadowed = match strategy {
+                    MatchStrategy::FirstMatch => !product.second_alone,
+                    MatchStrategy::LongestMatch => self.outlasts(later),
+                };
+                return Relation {
+                    shadowed,
+                    overlap: product.overlap,
+                };
+            }
+        }
+
+        // Without automata, only rules with finitely many matches are handled.
+        // Literals are matched in isolation, which says nothing about what an
+        // earlier rule with look-arounds does in context.
+        let overlap = later
+            .literals
+            .iter()
+            .flatten()
+            .find(|literal| self.regex.is_match(literal))
+            .or_else(|| {
+                self.literals
+                    .iter()
+                    .flatten()
+                    .find(|literal| later.regex.is_match(literal))
+            })
+            .cloned();
+        let shadowed = match strategy {
+            MatchStrategy::FirstMatch => {
+                !self.looks
+                    && later
+                        .literals
+                        .as_ref()
+                        .is_some_and(|literals| literals.iter().all(|l| self.regex.is_match(l)))
+            }
+            MatchStrategy::LongestMatch => self.outlasts(later),
+        };
+        Relation { shadowed, overlap }
+    }
+
+    // Whether this rule matches every string of `later` entirely, so that it
+    // wins the tie under the longest match strategy. Unknown, and so false, if
+    // this rule has look-arounds since the strings are matched in isolation.
+    fn outlasts(&self, later: &Language) -> bool {
+        !self.looks
+            && later.literals.as_ref().is_some_and(|literals| {
+                literals
+                    .iter()
+                    .all(|l| self.regex.find(l).is_some_and(|m| m.end() == l.len()))
+            })
+    }
+}
+
+// The state of one automaton while exploring inputs
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum Track {
+    Live(StateID),
+    // A prefix of the input matched, so every extension matches too
+    Matched,
+    // No extension of the input can match
+    Dead,
+}
+
+impl Track {
+    fn start(dfa: &dense::DFA<Vec<u32>>) -> Option<Self> {
+        let config = start::Config::new().anchored(Anchored::Yes);
+        dfa.start_state(&config).ok().map(Track::Live)
+    }
+
+    // Whether the input explored so far is matched, if it ends here
+    fn matches(self, dfa: &dense::DFA<Vec<u32>>) -> bool {
+        match self {
+            Track::Live(state) => dfa.is_match_state(dfa.next_eoi_state(state)),
+            Track::Matched => true,
+            Track::Dead => false,
+        }
+    }
+
+    // `None` if the automaton gave up on `byte`
+    fn next(self, dfa: &dense::DFA<Vec<u32>>, byte: u8) -> Option<Self> {
+        let Track::Live(state) = self else {
+            return Some(self);
+        };
+        let next = dfa.next_state(state, byte);
+        if dfa.is_quit_state(next) {
+            None
+        } else if dfa.is_match_state(next) {
+            Some(Track::Matched)
+        } else if dfa.is_dead_state(next) {
+            Some(Track::Dead)
+        } else {
+            Some(Track::Live(next))
+        }
+    }
+}
+
+// A pair of states, and the node and byte it was first reached from
+type Node = ((Track, Track), Option<(usize, u8)>);
+
+// What exploring the product of two automata found
+struct Product {
+    // The shortest input both automata match, if any
+    overlap: Option<String>,
+    // Whether some input is matched by the second automaton only
+    second_alone: bool,
+}
+
+// Explores the inputs both automata can read in lockstep, breadth first.
+// Returns `None` if the exploration was inconclusive.
+fn explore(first: &dense::DFA<Vec<u32>>, second: &dense::DFA<Vec<u32>>) -> Option<Product> {
+    // One byte per pair of equivalence classes is enough
+    let mut representatives = HashMap::new();
+    for byte in 0..=255u8 {
+        let classes = (
+            first.byte_classes().get(byte),
+            second.byte_classes().get(byte),
+        );
+        representatives.entry(classes).or_insert(byte);
+    }
+    let mut bytes: Vec<u8> = representatives.into_values().collect();
+    bytes.sort_unstable();
+
+    let start = (Track::start(first)?, Track::start(second)?);
+    let mut nodes: Vec<Node> = vec![(start, None)];
+    let mut seen = HashSet::from([start]);
+    let mut queue = VecDeque::from([0]);
+    let mut product = Product {
+        overlap: None,
+        second_alone: false,
+    };
+
+    while let Some(node) = queue.pop_front() {
+        let (a, b) = nodes[node].0;
+        let (first_matches, second_matches) = (a.matches(first), b.matches(second));
+        if first_matches && second_matches && product.overlap.is_none() {
+            product.overlap = Some(input_to(&nodes, node));
+        }
+        if second_matches && !first_matches {
+            product.second_alone = true;
+        }
+        if product.overlap.is_some() && product.second_alone {
+            break;
+        }
+
+        if !matches!(a, Track::Live(_)) && !matches!(b, Track::Live(_)) {
+            continue; // Extending the input changes nothing
+        }
+        for &byte in &bytes {
+            let next = (a.next(first, byte)?, b.next(second, byte)?);
+            if seen.insert(next) {
+                if nodes.len() == MAX_EXPLORED_STATES {
+                    return None;
+                }
+                nodes.push((next, Some((node, byte))));
+                queue.push_back(nodes.len() - 1);
+            }
+        }
+    }
+
+    Some(product)
+}
+
+// Rebuilds the input leading to `node`
+fn input_to(nodes: &[Node], mut node: usize) -> String {
+    let mut bytes = Vec::new();
+    while let Some((parent, byte)) = nodes[node].1 {
+        bytes.push(byte);
+        node = parent;
+    }
+    bytes.reverse();
+    String::from_utf8_lossy(&bytes).into_owned()
+}
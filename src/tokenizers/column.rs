@@ -0,0 +1,44 @@
+use unicode_segmentation::UnicodeSegmentation;
+
+/// `ColumnUnit` is the unit in which the tokenizer counts columns. Lines are
+/// always counted in `\n` characters and spans always in bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColumnUnit {
+    /// Unicode scalar values (`char`s). This is the default.
+    #[default]
+    Chars,
+
+    /// UTF-16 code units, as used by the Language Server Protocol.
+    Utf16,
+
+    /// Extended grapheme clusters, which match what a user sees as one
+    /// character on screen.
+    Graphemes,
+
+    /// UTF-8 bytes.
+    Bytes,
+}
+
+impl ColumnUnit {
+    /// Returns the width of `text` in this unit.
+    pub fn width(&self, text: &str) -> usize {
+        match self {
+            ColumnUnit::Chars => text.chars().count(),
+            ColumnUnit::Utf16 => text.encode_utf16().count(),
+            ColumnUnit::Graphemes => text.graphemes(true).count(),
+            ColumnUnit::Bytes => text.len(),
+        }
+    }
+
+    /// Returns the line and column reached after `text`, starting at `line` and
+    /// `column`.
+    pub(crate) fn advance(&self, line: usize, column: usize, text: &str) -> (usize, usize) {
+        match text.rfind('\n') {
+            Some(last) => (
+                line + text.matches('\n').count(),
+                1 + self.width(&text[last + 1..]),
+            ),
+            None => (line, column + self.width(text)),
+        }
+    }
+}
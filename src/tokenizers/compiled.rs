@@ -0,0 +1,125 @@
+use regex_automata::meta::{BuildError, Regex};
+use regex_automata::{Anchored, Input};
+
+use super::dispatch::Dispatch;
+use super::mode::RuleEntry;
+use crate::rules::{FirstBytes, Match, RuleType};
+use crate::tokens::{BorrowedToken, TokenKind};
+
+// Below this many compiled rules among the candidates of a byte, running the
+// rules one by one is faster than searching the automaton
+const MIN_COMPILED_RULES: usize = 4;
+
+/// `CompiledRules` combines the regex and symbol rules of a mode into a single
+/// multi-pattern regex. An anchored search of it finds, in one pass, which of
+/// them is the first in order to match at the current position and how much
+/// it consumes, so the winning rule does not need to be run again and the
+/// others are not run at all. Rules that cannot be compiled (closures,
+/// callbacks and custom rules) are still run in turn.
+#[derive(Clone)]
+pub(crate) struct CompiledRules {
+    regex: Regex,
+    // For every pattern of `regex`, the index of its rule in the mode
+    rules: Vec<usize>,
+    // For every rule of the mode, whether it is one of the patterns
+    compiled: Vec<bool>,
+    // The bytes at which enough compiled rules are candidates for the search
+    // to pay off
+    bytes: FirstBytes,
+}
+
+impl CompiledRules {
+    pub(crate) fn new<K: TokenKind>(
+        rules: &[RuleEntry<K>],
+        dispatch: &Dispatch,
+    ) -> Result<Self, regex::Error> {
+        let mut sources = Vec::new();
+        let mut indices = Vec::new();
+
+        for (index, entry) in rules.iter().enumerate() {
+            let source = match &entry.rule {
+                // The same pattern, so the regex matches exactly when the rule does
+                RuleType::Regex(rule) => Some(rule.pattern().as_str().to_string()),
+                RuleType::Symbol(rule) => Some(format!("^{}", regex::escape(&rule.symbol))),
+                RuleType::SymbolTable(rule) => rule.pattern().map(|p| format!("^(?:{})", p)),
+                _ => None,
+            };
+
+            if let Some(source) = source {
+                sources.push(source);
+                indices.push(index);
+            }
+        }
+
+        let mut compiled = vec![false; rules.len()];
+        for &index in &indices {
+            compiled[index] = true;
+        }
+        let bytes = (0..=u8::MAX)
+            .filter(|&byte| {
+                let candidates = dispatch.rules_for(byte);
+                candidates.iter().filter(|&&index| compiled[index]).count() >= MIN_COMPILED_RULES
+            })
+            .collect();
+
+        Ok(CompiledRules {
+            regex: Regex::new_many(&sources).map_err(regex_error)?,
+            rules: indices,
+            compiled,
+            bytes,
+        })
+    }
+
+    /// Returns whether searching the automaton is worth it at the start of
+    /// `input`.
+    pub(crate) fn applies_to(&self, input: &str) -> bool {
+        input
+            .as_bytes()
+            .first()
+            .is_some_and(|&byte| self.bytes.contains(byte))
+    }
+
+    /// Returns whether the rule at `index` is part of the automaton.
+    pub(crate) fn contains(&self, index: usize) -> bool {
+        self.compiled[index]
+    }
+
+    /// Returns the index of the first compiled rule matching at the start of
+    /// `input`, along with the length of its match.
+    pub(crate) fn find(&self, input: &str) -> Option<(usize, usize)> {
+        let found = self
+            .regex
+            .search(&Input::new(input).anchored(Anchored::Yes))?;
+        Some((self.rules[found.pattern().as_usize()], found.end()))
+    }
+}
+
+/// Builds the match of a compiled `rule` that `CompiledRules::find` found to
+/// consume `len` bytes of `input`, without matching it again.
+pub(crate) fn compiled_match<'a, K: TokenKind>(
+    rule: &'a RuleType<K>,
+    input: &'a str,
+    len: usize,
+) -> Option<Match<BorrowedToken<'a, K>>> {
+    let symbol = match rule {
+        RuleType::Regex(rule) => return rule.matched(input, len),
+        RuleType::Symbol(rule) => rule,
+        RuleType::SymbolTable(rule) => rule.longest_match(&input[..len])?,
+        _ => return None,
+    };
+    let token = BorrowedToken::new(
+        &symbol.token_type,
+        symbol.token_sub_type.as_deref(),
+        &input[..len],
+    );
+    Some(Match::new(len, token))
+}
+
+// Reports build errors the way the regex crate does, so that
+// `GrammarError::Compilation` keeps its error type
+fn regex_error(error: BuildError) -> regex::Error {
+    match error.size_limit() {
+        Some(limit) => regex::Error::CompiledTooBig(limit),
+        None => regex::Error::Syntax(error.to_string()),
+    }
+}
@@ -0,0 +1,45 @@
+use crate::rules::FirstBytes;
+
+/// `Dispatch` maps every byte to the rules of a mode that can match at a
+/// position starting with it, in the order the rules were added. It is kept
+/// up to date as rules are added, so it needs no compilation step.
+#[derive(Clone)]
+pub(crate) struct Dispatch {
+    by_byte: Vec<Vec<usize>>,
+    // Every rule, for the end of the input where there is no byte to look at
+    all: Vec<usize>,
+}
+
+impl Dispatch {
+    pub(crate) fn new() -> Self {
+        Dispatch {
+            by_byte: vec![Vec::new(); 256],
+            all: Vec::new(),
+        }
+    }
+
+    /// Registers the rule at `index`, which must follow every rule already
+    /// registered.
+    pub(crate) fn push(&mut self, index: usize, first_bytes: FirstBytes) {
+        for (byte, rules) in self.by_byte.iter_mut().enumerate() {
+            if first_bytes.contains(byte as u8) {
+                rules.push(index);
+            }
+        }
+        self.all.push(index);
+    }
+
+    /// Returns the indices of the rules worth running against `input`.
+    pub(crate) fn candidates(&self, input: &str) -> &[usize] {
+        match input.as_bytes().first() {
+            Some(&byte) => self.rules_for(byte),
+            None => &self.all,
+        }
+    }
+
+    /// Returns the indices of the rules that can match at a position starting
+    /// with `byte`.
+    pub(crate) fn rules_for(&self, byte: u8) -> &[usize] {
+        &self.by_byte[byte as usize]
+    }
+}
@@ -0,0 +1,13 @@
+/// `MatchStrategy` decides which rule wins when more than one rule matches at
+/// the current position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MatchStrategy {
+    /// The first rule (in registration order) that matches wins. This is the
+    /// default and requires longer symbols to be registered before their prefixes.
+    #[default]
+    FirstMatch,
+
+    /// Every rule is tried and the one consuming the most input wins
+    /// (maximal munch). Ties are broken by registration order.
+    LongestMatch,
+}
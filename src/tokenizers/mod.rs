@@ -1,3 +1,19 @@
+pub mod analysis;
+pub mod column;
+mod compiled;
+mod dispatch;
+pub mod match_strategy;
+pub mod mode;
+pub mod recovery;
+pub mod token_stream;
 pub mod tokenizer;
+pub mod whitespace;
 
+pub use analysis::GrammarIssue;
+pub use column::ColumnUnit;
+pub use match_strategy::MatchStrategy;
+pub use mode::{ModeAction, RuleHandle, DEFAULT_MODE};
+pub use recovery::{RecoveryPolicy, ERROR_TOKEN_TYPE};
+pub use token_stream::{BorrowedTokenStream, TokenStream};
 pub use tokenizer::Tokenizer;
+pub use whitespace::{WhitespacePolicy, NEWLINE_TOKEN_TYPE, WHITESPACE_TOKEN_TYPE};
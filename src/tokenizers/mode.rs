@@ -0,0 +1,103 @@
+use super::compiled::CompiledRules;
+use super::dispatch::Dispatch;
+use super::WhitespacePolicy;
+use crate::rules::{KeywordTable, RuleType};
+use crate::tokens::TokenKind;
+
+/// `DEFAULT_MODE` is the name of the mode every tokenization starts in. Rules
+/// added outside of `Tokenizer::with_mode` belong to it.
+pub const DEFAULT_MODE: &str = "default";
+
+/// `ModeAction` changes the active lexer mode after the rule it is attached to
+/// matches, whether or not the match emits a token.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ModeAction {
+    /// Enter the named mode, returning to the current one on `Pop`.
+    Push(String),
+
+    /// Return to the mode that was active before the last `Push`. Popping the
+    /// default mode is ignored so that unbalanced input cannot empty the stack.
+    Pop,
+
+    /// Replace the current mode with the named mode.
+    Switch(String),
+}
+
+/// `Mode` is a named set of rules that are only active while the mode is on
+/// top of the mode stack.
+#[derive(Clone)]
+pub(crate) struct Mode<K: TokenKind> {
+    pub(crate) name: String,
+    pub(crate) rules: Vec<RuleEntry<K>>,
+    // Number of rules added to the mode, including the ones rejected by the
+    // `try_add_*` methods
+    pub(crate) added: usize,
+    // Overrides the tokenizer-wide policy while the mode is active
+    pub(crate) whitespace_policy: Option<WhitespacePolicy>,
+    // Set by `Tokenizer::compile` and dropped whenever a rule is added
+    pub(crate) compiled: Option<CompiledRules>,
+    // Narrows the rules to try by the first byte of the input
+    pub(crate) dispatch: Dispatch,
+}
+
+impl<K: TokenKind> Mode<K> {
+    pub(crate) fn new(name: &str) -> Self {
+        Mode {
+            name: name.to_string(),
+            rules: Vec::new(),
+            added: 0,
+            whitespace_policy: None,
+            compiled: None,
+            dispatch: Dispatch::new(),
+        }
+    }
+}
+
+/// `RuleEntry` is a rule registered in a mode together with the mode action it
+/// triggers and the keywords its tokens are checked against.
+#[derive(Clone)]
+pub(crate) struct RuleEntry<K: TokenKind> {
+    // Index reported for the rule, which counts the rejected rules added
+    // before it so that it agrees with the indices of `GrammarError`s
+    pub(crate) index: usize,
+    pub(crate) rule: RuleType<K>,
+    pub(crate) action: Option<ModeAction>,
+    pub(crate) keywords: Option<KeywordTable<K>>,
+}
+
+/// `RuleHandle` is returned when a rule is added to a `Tokenizer` and allows
+/// attaching a mode action or a keyword table to it.
+pub struct RuleHandle<'t, K: TokenKind = String> {
+    entry: &'t mut RuleEntry<K>,
+}
+
+impl<'t, K: TokenKind> RuleHandle<'t, K> {
+    pub(crate) fn new(entry: &'t mut RuleEntry<K>) -> Self {
+        RuleHandle { entry }
+    }
+
+    /// Enters the mode `name` after this rule matches.
+    pub fn push_mode(self, name: &str) -> Self {
+        self.entry.action = Some(ModeAction::Push(name.to_string()));
+        self
+    }
+
+    /// Returns to the previous mode after this rule matches.
+    pub fn pop_mode(self) -> Self {
+        self.entry.action = Some(ModeAction::Pop);
+        self
+    }
+
+    /// Replaces the current mode with `name` after this rule matches.
+    pub fn switch_mode(self, name: &str) -> Self {
+        self.entry.action = Some(ModeAction::Switch(name.to_string()));
+        self
+    }
+
+    /// Retypes the tokens of this rule whose value is one of the words of
+    /// `keywords`, see `KeywordTable`.
+    pub fn keywords(self, keywords: KeywordTable<K>) -> Self {
+        self.entry.keywords = Some(keywords);
+        self
+    }
+}
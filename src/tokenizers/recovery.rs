@@ -0,0 +1,46 @@
+/// `ERROR_TOKEN_TYPE` is the `token_type` of the tokens emitted for input that
+/// was skipped while recovering from an unrecognized character, when token
+/// types are `String`s. See `TokenKind::error`.
+pub const ERROR_TOKEN_TYPE: &str = "Error";
+
+/// `RecoveryPolicy` decides what the tokenizer does when no rule matches at the
+/// current position.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum RecoveryPolicy {
+    /// Report the unrecognized character and stop. This is the default.
+    #[default]
+    Halt,
+
+    /// Skip the offending character and continue with the next one.
+    SkipChar,
+
+    /// Skip up to (but not including) the next whitespace character, as
+    /// configured with `Tokenizer::set_whitespace_chars`.
+    SkipToWhitespace,
+
+    /// Skip up to (but not including) the next character in the sync set, so
+    /// that the sync character itself is tokenized normally.
+    SkipToSync(Vec<char>),
+}
+
+impl RecoveryPolicy {
+    /// Returns the number of bytes to skip at the start of `input`, or `None`
+    /// if the policy does not recover. At least one character is always skipped.
+    pub(crate) fn skip_len(
+        &self,
+        input: &str,
+        is_whitespace: impl Fn(char) -> bool,
+    ) -> Option<usize> {
+        let first = input.chars().next()?;
+        let rest = &input[first.len_utf8()..];
+        let rest_len = match self {
+            RecoveryPolicy::Halt => return None,
+            RecoveryPolicy::SkipChar => 0,
+            RecoveryPolicy::SkipToWhitespace => rest.find(is_whitespace).unwrap_or(rest.len()),
+            RecoveryPolicy::SkipToSync(sync) => {
+                rest.find(|c| sync.contains(&c)).unwrap_or(rest.len())
+            }
+        };
+        Some(first.len_utf8() + rest_len)
+    }
+}
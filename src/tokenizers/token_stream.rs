@@ -0,0 +1,377 @@
+use std::any::Any;
+use std::collections::VecDeque;
+
+use std::borrow::Cow;
+
+use super::whitespace::whitespace_len;
+use super::{ModeAction, Tokenizer, WhitespacePolicy};
+use crate::rules::RuleContext;
+use crate::tokens::{BorrowedToken, Location, Span, Token, TokenKind, TokenizationError};
+
+/// `TokenStream` lazily tokenizes an input, yielding one token (or error) at a
+/// time. It is created by `Tokenizer::tokens` and carries the line and column
+/// state, as well as the lexer mode stack, between calls to `next`.
+pub struct TokenStream<'a, K: TokenKind = String> {
+    inner: BorrowedTokenStream<'a, K>,
+}
+
+impl<'a, K: TokenKind> TokenStream<'a, K> {
+    pub(crate) fn new(tokenizer: &'a Tokenizer<K>, input: &'a str) -> Self {
+        TokenStream {
+            inner: BorrowedTokenStream::new(tokenizer, input),
+        }
+    }
+
+    /// Makes `state` available to the rules through `RuleContext::state`.
+    pub fn with_state(self, state: &'a mut (dyn Any + Send)) -> Self {
+        TokenStream {
+            inner: self.inner.with_state(state),
+        }
+    }
+
+    /// The line the stream will resume tokenizing from.
+    pub fn line(&self) -> usize {
+        self.inner.line()
+    }
+
+    /// The column the stream will resume tokenizing from.
+    pub fn column(&self) -> usize {
+        self.inner.column()
+    }
+
+    /// The name of the innermost active lexer mode.
+    pub fn mode(&self) -> &str {
+        self.inner.mode()
+    }
+
+    /// The names of the active lexer modes, from the outermost to the innermost.
+    pub fn mode_stack(&self) -> Vec<&str> {
+        self.inner.mode_stack()
+    }
+}
+
+impl<K: TokenKind> Iterator for TokenStream<'_, K> {
+    type Item = Result<Token<K>, TokenizationError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner
+            .next()
+            .map(|item| item.map(BorrowedToken::into_owned))
+    }
+}
+
+impl<K: TokenKind> std::iter::FusedIterator for TokenStream<'_, K> {}
+
+/// `BorrowedTokenStream` is the zero-copy counterpart of `TokenStream`,
+/// created by `Tokenizer::borrowed_tokens`. Its tokens borrow from both the
+/// input and the tokenizer.
+pub struct BorrowedTokenStream<'a, K: TokenKind = String> {
+    tokenizer: &'a Tokenizer<K>,
+    input: &'a str,
+    // Byte offset of the next character to scan
+    offset: usize,
+    current_line: usize,
+    current_column: usize,
+    // Indices into the tokenizer's modes, innermost last; never empty
+    mode_stack: Vec<usize>,
+    // Items produced at the current position but not yet handed out
+    pending: VecDeque<Result<BorrowedToken<'a, K>, TokenizationError>>,
+    // The last token emitted, and the last one that is not trivia when that
+    // is an earlier token, so that each token is only copied once
+    previous: Option<BorrowedToken<'a, K>>,
+    previous_significant: Option<BorrowedToken<'a, K>>,
+    state: Option<RunState<'a>>,
+    finished: bool,
+}
+
+// User state of a run: passed in by the caller, or created by the tokenizer
+enum RunState<'a> {
+    Borrowed(&'a mut (dyn Any + Send)),
+    Owned(Box<dyn Any + Send>),
+}
+
+impl RunState<'_> {
+    fn get(&mut self) -> &mut dyn Any {
+        match self {
+            RunState::Borrowed(state) => &mut **state,
+            RunState::Owned(state) => &mut **state,
+        }
+    }
+}
+
+impl<'a, K: TokenKind> BorrowedTokenStream<'a, K> {
+    pub(crate) fn new(tokenizer: &'a Tokenizer<K>, input: &'a str) -> Self {
+        BorrowedTokenStream {
+            tokenizer,
+            input,
+            offset: 0,
+            current_line: 1,
+            current_column: 1, // Start column counting from 1
+            mode_stack: vec![0],
+            pending: VecDeque::new(),
+            previous: None,
+            previous_significant: None,
+            state: tokenizer.initial_state().map(RunState::Owned),
+            finished: false,
+        }
+    }
+
+    /// Makes `state` available to the rules through `RuleContext::state`.
+    pub fn with_state(mut self, state: &'a mut (dyn Any + Send)) -> Self {
+        self.state = Some(RunState::Borrowed(state));
+        self
+    }
+
+    /// The line the stream will resume tokenizing from.
+    pub fn line(&self) -> usize {
+        self.current_line
+    }
+
+    /// The column the stream will resume tokenizing from.
+    pub fn column(&self) -> usize {
+        self.current_column
+    }
+
+    /// The name of the innermost active lexer mode.
+    pub fn mode(&self) -> &'a str {
+        self.tokenizer.mode_name(*self.mode_stack.last().unwrap())
+    }
+
+    /// The names of the active lexer modes, from the outermost to the innermost.
+    pub fn mode_stack(&self) -> Vec<&'a str> {
+        self.mode_stack
+            .iter()
+            .map(|&index| self.tokenizer.mode_name(index))
+            .collect()
+    }
+
+    // Moves past the next `len` bytes, updating the line and column.
+    fn advance(&mut self, len: usize) {
+        let end = self.offset + len;
+        (self.current_line, self.current_column) = self.tokenizer.column_unit().advance(
+            self.current_line,
+            self.current_column,
+            &self.input[self.offset..end],
+        );
+        self.offset = end;
+    }
+
+    // Rebases a span relative to `start` onto the source and computes the
+    // line and column it begins at.
+    fn locate(&self, start: usize, span: Span) -> Location {
+        let mut end = (start + span.start).min(self.input.len());
+        while !self.input.is_char_boundary(end) {
+            end -= 1;
+        }
+        let (line, column) = self.tokenizer.column_unit().advance(
+            self.current_line,
+            self.current_column,
+            &self.input[start..end],
+        );
+        Location {
+            mode_stack: self.mode_stack().iter().map(|s| s.to_string()).collect(),
+            ..Location::new(
+                line,
+                column,
+                Span::new(start + span.start, start + span.end),
+            )
+        }
+    }
+
+    fn apply_mode_action(&mut self, action: &'a ModeAction, start: usize, len: usize) {
+        let target = match action {
+            ModeAction::Pop => {
+                if self.mode_stack.len() > 1 {
+                    self.mode_stack.pop();
+                }
+                return;
+            }
+            ModeAction::Push(name) | ModeAction::Switch(name) => name,
+        };
+
+        match self.tokenizer.mode_index(target) {
+            Some(index) => {
+                if let ModeAction::Switch(_) = action {
+                    self.mode_stack.pop();
+                }
+                self.mode_stack.push(index);
+            }
+            None => {
+                // Reported by `Tokenizer::compile`, if it was called
+                let message = format!("unknown lexer mode: {}", target);
+                let mut e = TokenizationError::custom(&message, Span::new(0, len));
+                *e.location_mut() = self.locate(start, e.span());
+                self.pending.push_back(Err(e));
+            }
+        }
+    }
+
+    // Queues `token` and remembers it for the context of the next rules.
+    fn emit(&mut self, token: BorrowedToken<'a, K>) {
+        if !token.token_type.is_trivia() {
+            self.previous_significant = None;
+        } else if let Some(previous) = self.previous.take() {
+            if !previous.token_type.is_trivia() {
+                self.previous_significant = Some(previous);
+            }
+        }
+        self.previous = Some(token.clone());
+        self.pending.push_back(Ok(token));
+    }
+
+    fn unrecognized(&self, start: usize, len: usize) -> TokenizationError {
+        TokenizationError::UnrecognizedToken {
+            lexeme: self.input[start..start + len].to_string(),
+            location: self.locate(start, Span::new(0, len)),
+        }
+    }
+
+    // Scans the next position, queueing whatever it produced into `pending`.
+    fn scan(&mut self) {
+        while let Some(next_char) = self.input[self.offset..].chars().next() {
+            let start = self.offset;
+            let current_input = &self.input[start..];
+            let mode = *self.mode_stack.last().unwrap();
+
+            let whitespace_policy = self.tokenizer.mode_whitespace_policy(mode);
+            if whitespace_policy != WhitespacePolicy::Delegate
+                && self.tokenizer.is_whitespace(next_char)
+            {
+                if whitespace_policy == WhitespacePolicy::Skip {
+                    self.advance(next_char.len_utf8());
+                    continue;
+                }
+
+                let (len, is_newline) =
+                    whitespace_len(current_input, |c| self.tokenizer.is_whitespace(c));
+                let kind = if is_newline {
+                    K::newline()
+                } else {
+                    K::whitespace()
+                };
+                self.emit(BorrowedToken {
+                    token_type: Cow::Owned(kind),
+                    token_sub_type: None,
+                    value: Cow::Borrowed(&current_input[..len]),
+                    line: self.current_line,
+                    column: self.current_column,
+                    span: Span::new(start, start + len),
+                    captures: Vec::new(),
+                });
+                self.advance(len);
+                return;
+            }
+
+            let previous_significant = match &self.previous {
+                Some(previous) if !previous.token_type.is_trivia() => Some(previous),
+                _ => self.previous_significant.as_ref(),
+            };
+            let mut context = RuleContext {
+                source: self.input,
+                offset: start,
+                line: self.current_line,
+                column: self.current_column,
+                mode: self.tokenizer.mode_name(mode),
+                previous: self.previous.as_ref(),
+                previous_significant,
+                state: self.state.as_mut().map(RunState::get),
+            };
+            let mut errors = Vec::new();
+            let best = self
+                .tokenizer
+                .match_rules(mode, current_input, &mut context, &mut errors);
+            for mut e in errors {
+                let location = self.locate(start, e.span());
+                *e.location_mut() = location;
+                self.pending.push_back(Err(e));
+            }
+
+            match best {
+                Some((mut m, action)) => {
+                    for mut e in m.errors.drain(..) {
+                        let location = self.locate(start, e.span());
+                        *e.location_mut() = location;
+                        self.pending.push_back(Err(e));
+                    }
+
+                    let token_len = m.consumed;
+                    if token_len == 0 || !current_input.is_char_boundary(token_len) {
+                        // Advancing would loop forever or split a character
+                        let message = format!("rule consumed an invalid length: {}", token_len);
+                        let mut e = TokenizationError::custom(&message, Span::new(0, 0));
+                        *e.location_mut() = self.locate(start, e.span());
+                        self.pending.push_back(Err(e));
+                        self.finished = true;
+                        return;
+                    }
+
+                    if let Some(mut token) = m.token {
+                        for capture in &mut token.captures {
+                            capture.span =
+                                Span::new(start + capture.span.start, start + capture.span.end);
+                        }
+                        self.emit(BorrowedToken {
+                            line: self.current_line,
+                            column: self.current_column, // Use current column for the token
+                            span: Span::new(start, start + token_len),
+                            ..token
+                        });
+                    }
+                    if let Some(action) = action {
+                        self.apply_mode_action(action, start, token_len);
+                    }
+                    self.advance(token_len);
+                }
+                None => match self
+                    .tokenizer
+                    .recovery_policy()
+                    .skip_len(current_input, |c| self.tokenizer.is_whitespace(c))
+                {
+                    Some(skip_len) => {
+                        // Report the skipped text and keep it in the stream as an error token
+                        let lexeme = &current_input[..skip_len];
+                        self.pending
+                            .push_back(Err(self.unrecognized(start, skip_len)));
+                        self.emit(BorrowedToken {
+                            line: self.current_line,
+                            column: self.current_column,
+                            span: Span::new(start, start + skip_len),
+                            token_type: Cow::Owned(K::error()),
+                            token_sub_type: None,
+                            value: Cow::Borrowed(lexeme),
+                            captures: Vec::new(),
+                        });
+                        self.advance(skip_len);
+                    }
+                    None => {
+                        // No rule matched, the rest of the input cannot be tokenized
+                        self.pending
+                            .push_back(Err(self.unrecognized(start, next_char.len_utf8())));
+                        self.finished = true;
+                    }
+                },
+            }
+            return;
+        }
+
+        self.finished = true;
+    }
+}
+
+impl<'a, K: TokenKind> Iterator for BorrowedTokenStream<'a, K> {
+    type Item = Result<BorrowedToken<'a, K>, TokenizationError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(item) = self.pending.pop_front() {
+                return Some(item);
+            }
+            if self.finished {
+                return None;
+            }
+            self.scan();
+        }
+    }
+}
+
+impl<K: TokenKind> std::iter::FusedIterator for BorrowedTokenStream<'_, K> {}
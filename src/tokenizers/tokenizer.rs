@@ -1,114 +1,461 @@
-use crate::rules::{self, CallbackRule, RegexRule, Rule, RuleType, SymbolRule};
-use crate::tokens::{Token, TokenizationError};
-pub struct Tokenizer {
-    rules: Vec<RuleType>,
+use std::any::Any;
+use std::sync::Arc;
+
+use super::analysis;
+use super::compiled::{compiled_match, CompiledRules};
+use super::mode::{Mode, ModeAction, RuleEntry, DEFAULT_MODE};
+use super::{
+    BorrowedTokenStream, ColumnUnit, GrammarIssue, MatchStrategy, RecoveryPolicy, RuleHandle,
+    TokenStream, WhitespacePolicy,
+};
+use crate::rules::{
+    self, Match, RegexRule, Rule, RuleContext, RuleType, SymbolRule, SymbolTableRule,
+};
+use crate::tokens::{BorrowedToken, GrammarError, Token, TokenKind, TokenizationError};
+
+// Creates the user state of a tokenization run
+type StateFactory = dyn Fn() -> Box<dyn Any + Send> + Send + Sync;
+
+/// `Tokenizer` splits an input into tokens according to its rules.
+///
+/// Token types are `String`s by default. To use a user-defined kind, such as
+/// an enum implementing `TokenKind`, create the tokenizer with
+/// `Tokenizer::<Kind>::default()`.
+///
+/// A `Tokenizer` is `Send + Sync`, so it can be built once and shared between
+/// threads, for instance in a `static` or an `Arc`. Clones share their closure,
+/// callback and custom rules instead of copying them, so a clone can be
+/// extended without rebuilding the whole grammar.
+#[derive(Clone)]
+pub struct Tokenizer<K: TokenKind = String> {
+    // modes[0] is always the default mode
+    modes: Vec<Mode<K>>,
+    // Mode that newly added rules are registered in
+    target_mode: usize,
+    match_strategy: MatchStrategy,
+    recovery_policy: RecoveryPolicy,
+    whitespace_policy: WhitespacePolicy,
+    // `None` means `char::is_whitespace`
+    whitespace_chars: Option<Vec<char>>,
+    column_unit: ColumnUnit,
+    state_factory: Option<Arc<StateFactory>>,
+    // Errors from the `try_add_*` methods, reported again by `compile`
+    grammar_errors: Vec<GrammarError>,
+}
+
+impl<K: TokenKind> Default for Tokenizer<K> {
+    fn default() -> Self {
+        Tokenizer {
+            modes: vec![Mode::new(DEFAULT_MODE)],
+            target_mode: 0,
+            match_strategy: MatchStrategy::default(),
+            recovery_policy: RecoveryPolicy::default(),
+            whitespace_policy: WhitespacePolicy::default(),
+            whitespace_chars: None,
+            column_unit: ColumnUnit::default(),
+            state_factory: None,
+            grammar_errors: Vec::new(),
+        }
+    }
 }
 
 impl Tokenizer {
     pub fn new() -> Self {
-        Tokenizer { rules: Vec::new() }
+        Self::default()
+    }
+}
+
+impl<K: TokenKind> Tokenizer<K> {
+    /// Sets the strategy used to pick a token when several rules match at the
+    /// same position. Defaults to `MatchStrategy::FirstMatch`.
+    pub fn set_match_strategy(&mut self, strategy: MatchStrategy) {
+        self.match_strategy = strategy;
+    }
+
+    pub fn match_strategy(&self) -> MatchStrategy {
+        self.match_strategy
+    }
+
+    /// Sets what happens when no rule matches at the current position.
+    /// Defaults to `RecoveryPolicy::Halt`.
+    pub fn set_recovery_policy(&mut self, policy: RecoveryPolicy) {
+        self.recovery_policy = policy;
+    }
+
+    pub fn recovery_policy(&self) -> &RecoveryPolicy {
+        &self.recovery_policy
+    }
+
+    /// Sets the unit `Token::column` is counted in. Defaults to
+    /// `ColumnUnit::Chars`.
+    pub fn set_column_unit(&mut self, unit: ColumnUnit) {
+        self.column_unit = unit;
+    }
+
+    pub fn column_unit(&self) -> ColumnUnit {
+        self.column_unit
+    }
+
+    /// Gives every tokenization run its own copy of `initial` as user state,
+    /// available to the rules through `RuleContext::state`. The state is reset
+    /// for each run, so the tokenizer can be reused (and shared between
+    /// threads) while the rules keep per-run state such as a nesting depth.
+    ///
+    /// State passed with `TokenStream::with_state` or `tokenize_with_state`
+    /// takes precedence.
+    pub fn set_state<S: Any + Clone + Send + Sync>(&mut self, initial: S) {
+        self.state_factory = Some(Arc::new(move || Box::new(initial.clone())));
+    }
+
+    pub(crate) fn initial_state(&self) -> Option<Box<dyn Any + Send>> {
+        self.state_factory.as_ref().map(|factory| factory())
+    }
+
+    /// Sets how whitespace is treated before the rules are consulted. Defaults
+    /// to `WhitespacePolicy::Skip`.
+    ///
+    /// Called inside `with_mode`, the policy only applies while that mode is
+    /// active; otherwise it applies to every mode without a policy of its own.
+    pub fn set_whitespace_policy(&mut self, policy: WhitespacePolicy) {
+        if self.target_mode == 0 {
+            self.whitespace_policy = policy;
+        } else {
+            self.modes[self.target_mode].whitespace_policy = Some(policy);
+        }
+    }
+
+    /// Returns the whitespace policy in effect while `mode` is active.
+    pub fn whitespace_policy(&self, mode: &str) -> WhitespacePolicy {
+        self.mode_index(mode)
+            .and_then(|index| self.modes[index].whitespace_policy)
+            .unwrap_or(self.whitespace_policy)
     }
 
-    pub fn add_rule(&mut self, rule: Box<dyn rules::Rule>) {
-        self.rules.push(RuleType::Rule(rule));
+    /// Restricts the characters treated as whitespace to `chars`. By default
+    /// every character for which `char::is_whitespace` holds is whitespace.
+    pub fn set_whitespace_chars(&mut self, chars: &[char]) {
+        self.whitespace_chars = Some(chars.to_vec());
     }
 
+    /// Returns whether `c` is whitespace for this tokenizer.
+    pub fn is_whitespace(&self, c: char) -> bool {
+        match &self.whitespace_chars {
+            Some(chars) => chars.contains(&c),
+            None => c.is_whitespace(),
+        }
+    }
+
+    /// Registers the rules added by `define` in the mode `name`, creating the
+    /// mode if it does not exist yet. Rules added outside of `with_mode` belong
+    /// to `DEFAULT_MODE`.
+    pub fn with_mode<F>(&mut self, name: &str, define: F)
+    where
+        F: FnOnce(&mut Tokenizer<K>),
+    {
+        let index = match self.mode_index(name) {
+            Some(index) => index,
+            None => {
+                self.modes.push(Mode::new(name));
+                self.modes.len() - 1
+            }
+        };
+
+        let previous = std::mem::replace(&mut self.target_mode, index);
+        define(self);
+        self.target_mode = previous;
+    }
+
+    pub fn add_rule(&mut self, rule: Box<dyn rules::Rule<K>>) -> RuleHandle<'_, K> {
+        self.push_rule(RuleType::Rule(rule.into()))
+    }
+
+    /// Adds a rule matching the regular expression `pattern` at the current
+    /// position. The pattern is anchored automatically, so a leading `^` is
+    /// optional.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pattern` is not a valid regular expression or can match the
+    /// empty string; see `try_add_regex_rule`.
     pub fn add_regex_rule(
         &mut self,
         pattern: &str,
-        token_type: &str,
+        token_type: impl Into<K>,
         sub_token_type: Option<&str>,
-    ) {
+    ) -> RuleHandle<'_, K> {
         let rule = RuleType::Regex(RegexRule::new(pattern, token_type, sub_token_type));
-        self.rules.push(rule);
+        self.push_rule(rule)
+    }
+
+    /// Adds a regex rule built beforehand, for instance to configure it with
+    /// `RegexRule::with_group_subtypes`.
+    pub fn add_regex(&mut self, rule: RegexRule<K>) -> RuleHandle<'_, K> {
+        self.push_rule(RuleType::Regex(rule))
     }
 
-    pub fn add_symbol_rule(&mut self, symbol: &str, token_type: &str, default_rule: Option<&str>) {
+    /// Same as `add_regex_rule`, but returns an error instead of panicking if
+    /// `pattern` is not a valid regular expression or can match the empty
+    /// string. The error is also kept by
+    /// the tokenizer and reported by `compile`, so a grammar can be built in
+    /// one go and all of its errors collected at the end:
+    ///
+    /// ```
+    /// use rb_tokenizer::Tokenizer;
+    ///
+    /// let mut tokenizer = Tokenizer::new();
+    /// let _ = tokenizer.try_add_regex_rule(r"^\d+", "Number", None);
+    /// let _ = tokenizer.try_add_regex_rule(r"^[a-z", "Identifier", None);
+    /// let _ = tokenizer.try_add_regex_rule(r"^(\+", "Operator", None);
+    ///
+    /// let errors = tokenizer.compile().unwrap_err();
+    /// assert_eq!(errors.len(), 2);
+    /// ```
+    pub fn try_add_regex_rule(
+        &mut self,
+        pattern: &str,
+        token_type: impl Into<K>,
+        sub_token_type: Option<&str>,
+    ) -> Result<RuleHandle<'_, K>, GrammarError> {
+        match RegexRule::try_new(pattern, token_type, sub_token_type) {
+            Ok(rule) => Ok(self.push_rule(RuleType::Regex(rule))),
+            Err(e) => {
+                let mode = &mut self.modes[self.target_mode];
+                let e = e.in_rule(&mode.name, mode.added);
+                mode.added += 1;
+                self.grammar_errors.push(e.clone());
+                Err(e)
+            }
+        }
+    }
+
+    /// Returns the errors reported by the `try_add_*` methods so far. Mode
+    /// actions entering unknown modes are only reported by `compile`, since a
+    /// mode may be defined after the rules that enter it.
+    pub fn grammar_errors(&self) -> &[GrammarError] {
+        &self.grammar_errors
+    }
+
+    pub fn add_symbol_rule(
+        &mut self,
+        symbol: &str,
+        token_type: impl Into<K>,
+        default_rule: Option<&str>,
+    ) -> RuleHandle<'_, K> {
         let rule = RuleType::Symbol(SymbolRule::new(symbol, token_type, default_rule));
-        self.rules.push(rule);
+        self.push_rule(rule)
     }
 
-    pub fn add_closure_rule(
+    /// Adds a single rule matching the longest of `symbols`, given as
+    /// `(symbol, token_type, token_sub_type)`. Unlike separate symbol rules,
+    /// the order of the symbols does not matter, and the input is read once
+    /// for all of them.
+    ///
+    /// ```
+    /// use rb_tokenizer::Tokenizer;
+    ///
+    /// let mut tokenizer = Tokenizer::new();
+    /// tokenizer.add_symbols(&[
+    ///     ("<", "Operator", Some("Less")),
+    ///     ("<=", "Operator", Some("LessEqual")),
+    ///     ("<<", "Operator", Some("ShiftLeft")),
+    /// ]);
+    ///
+    /// let tokens = tokenizer.tokenize("<=<<<").unwrap();
+    /// let values: Vec<_> = tokens.iter().map(|t| t.value.as_str()).collect();
+    /// assert_eq!(values, vec!["<=", "<<", "<"]);
+    /// ```
+    pub fn add_symbols<T: Into<K> + Clone>(
         &mut self,
-        cb: Box<dyn Fn(&str) -> Result<Option<Token>, TokenizationError>>,
-    ) {
-        let rule = RuleType::Closure(rules::ClosureRule::new(cb));
-        self.rules.push(rule);
+        symbols: &[(&str, T, Option<&str>)],
+    ) -> RuleHandle<'_, K> {
+        let mut table = SymbolTableRule::new();
+        for (symbol, token_type, token_sub_type) in symbols {
+            table.insert(symbol, token_type.clone(), *token_sub_type);
+        }
+        self.push_rule(RuleType::SymbolTable(table))
     }
 
-    pub fn add_callback_rule(&mut self, cb: Box<dyn rules::CallbackRule>) {
-        let rule = RuleType::Callback(cb);
-        self.rules.push(rule);
+    /// Adds a symbol table built beforehand.
+    pub fn add_symbol_table(&mut self, rule: SymbolTableRule<K>) -> RuleHandle<'_, K> {
+        self.push_rule(RuleType::SymbolTable(rule))
     }
 
-    // Adjust the tokenize method to handle the Option for default_rule
-    pub fn tokenize(&self, input: &str) -> Result<Vec<Token>, Vec<TokenizationError>> {
-        let mut tokens = Vec::new();
-        let mut errors = Vec::new();
+    pub fn add_closure_rule(&mut self, cb: Box<rules::ClosureFn<K>>) -> RuleHandle<'_, K> {
+        let rule = RuleType::Closure(rules::ClosureRule::new(cb));
+        self.push_rule(rule)
+    }
 
-        let mut current_line = 1;
-        let mut current_column = 1; // Start column counting from 1
-        let mut chars = input.char_indices().peekable();
+    pub fn add_callback_rule(&mut self, cb: Box<dyn rules::CallbackRule<K>>) -> RuleHandle<'_, K> {
+        let rule = RuleType::Callback(cb.into());
+        self.push_rule(rule)
+    }
 
-        while let Some((start, next_char)) = chars.peek().copied() {
-            let mut matched = false;
+    fn push_rule(&mut self, rule: RuleType<K>) -> RuleHandle<'_, K> {
+        let mode = &mut self.modes[self.target_mode];
+        mode.compiled = None;
+        mode.dispatch.push(mode.rules.len(), rule.first_bytes());
+        mode.rules.push(RuleEntry {
+            index: mode.added,
+            rule,
+            action: None,
+            keywords: None,
+        });
+        mode.added += 1;
+        RuleHandle::new(mode.rules.last_mut().unwrap())
+    }
 
-            // Calculate whitespace and update column if necessary
-            if next_char.is_whitespace() {
-                if next_char == '\n' {
-                    current_line += 1;
-                    current_column = 1; // Reset column at new line
-                } else {
-                    current_column += 1; // Increment column for spaces
-                }
-                chars.next(); // Consume the whitespace character
-                continue; // Move to the next character
+    /// Compiles the regex and symbol rules of every mode into a single
+    /// multi-pattern automaton per mode. With `MatchStrategy::FirstMatch`, one
+    /// anchored search then tells which of them wins at a position and how much
+    /// it consumes, instead of running them in turn; `LongestMatch` still runs
+    /// every candidate rule. The search is only used at positions where the
+    /// first byte leaves several regex and symbol rules to try, since running
+    /// a few rules directly is faster. Matching results are unchanged.
+    ///
+    /// Adding a rule to a mode discards its compiled form, so call this once
+    /// the grammar is complete.
+    ///
+    /// Fails with every error found while building the grammar, including the
+    /// ones already returned by the `try_add_*` methods and the mode actions
+    /// whose target mode is never defined.
+    pub fn compile(&mut self) -> Result<(), Vec<GrammarError>> {
+        let mut errors = self.grammar_errors.clone();
+        errors.extend(self.unknown_modes());
+        for mode in &mut self.modes {
+            match CompiledRules::new(&mode.rules, &mode.dispatch) {
+                Ok(compiled) => mode.compiled = Some(compiled),
+                Err(error) => errors.push(GrammarError::Compilation {
+                    mode: mode.name.clone(),
+                    error,
+                }),
             }
+        }
 
-            let current_input = &input[start..];
-
-            for rule in &self.rules {
-                match rule.process(current_input) {
-                    Ok(Some(token)) => {
-                        let token_len = token.value.len();
-                        tokens.push(Token {
-                            line: current_line,
-                            column: current_column, // Use current column for the token
-                            ..token
-                        });
-                        // Advance the iterator by token_len characters and update column accordingly
-                        for _ in 0..token_len {
-                            if let Some((_, char)) = chars.next() {
-                                if char == '\n' {
-                                    current_line += 1;
-                                    current_column = 1;
-                                } else {
-                                    current_column += 1;
-                                }
-                            }
-                        }
-                        matched = true;
-                        break; // Break to process the next segment of input
-                    }
-                    Ok(None) => {} // No match, continue to next rule
-                    Err(e) => {
-                        print!(
-                            "Error while processing input: {:?} at line: {:?} and column: {:?}\n",
-                            e, current_line, current_column
-                        );
-                        errors.push(e)
-                    } // Error encountered
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    // Mode actions entering a mode that was never defined with `with_mode`
+    fn unknown_modes(&self) -> Vec<GrammarError> {
+        let mut errors = Vec::new();
+        for mode in &self.modes {
+            for entry in &mode.rules {
+                let target = match &entry.action {
+                    Some(ModeAction::Push(target) | ModeAction::Switch(target)) => target,
+                    _ => continue,
+                };
+                if self.mode_index(target).is_none() {
+                    errors.push(GrammarError::UnknownMode {
+                        target: target.clone(),
+                        mode: mode.name.clone(),
+                        rule_index: entry.index,
+                    });
                 }
             }
+        }
+        errors
+    }
 
-            if !matched {
-                // If no rules matched, consider handling or reporting it
-                errors.push(TokenizationError::UnrecognizedToken(
-                    current_input.to_string(),
-                ));
-                break; // Or handle it differently based on your needs
-            }
+    /// Looks for likely mistakes in the symbol and regex rules of every mode:
+    /// rules that can never produce a token because an earlier rule always
+    /// wins (under the current match strategy), rules that can match at the
+    /// same position, and rules that can match the empty string.
+    ///
+    /// Regex rules are compared by exploring their automata together, which
+    /// is exact but can be expensive, so this is meant for grammar tests
+    /// rather than for every run. Pairs too large to explore fall back to
+    /// comparing the strings they match when there are finitely many, and
+    /// are skipped otherwise.
+    ///
+    /// ```
+    /// use rb_tokenizer::tokenizers::GrammarIssue;
+    /// use rb_tokenizer::Tokenizer;
+    ///
+    /// let mut tokenizer = Tokenizer::new();
+    /// tokenizer.add_symbol_rule("<", "Operator", Some("Less"));
+    /// tokenizer.add_symbol_rule("<=", "Operator", Some("LessEqual"));
+    ///
+    /// assert_eq!(
+    ///     tokenizer.analyze(),
+    ///     vec![GrammarIssue::Shadowed {
+    ///         mode: "default".to_string(),
+    ///         rule: 1,
+    ///         by: 0
+    ///     }]
+    /// );
+    /// ```
+    pub fn analyze(&self) -> Vec<GrammarIssue> {
+        self.modes
+            .iter()
+            .flat_map(|mode| analysis::analyze_mode(&mode.name, &mode.rules, self.match_strategy))
+            .collect()
+    }
+
+    /// Returns whether every mode has been compiled with `compile`.
+    pub fn is_compiled(&self) -> bool {
+        self.modes.iter().all(|mode| mode.compiled.is_some())
+    }
+
+    pub(crate) fn mode_index(&self, name: &str) -> Option<usize> {
+        self.modes.iter().position(|mode| mode.name == name)
+    }
+
+    pub(crate) fn mode_name(&self, index: usize) -> &str {
+        &self.modes[index].name
+    }
+
+    pub(crate) fn mode_whitespace_policy(&self, index: usize) -> WhitespacePolicy {
+        self.modes[index]
+            .whitespace_policy
+            .unwrap_or(self.whitespace_policy)
+    }
+
+    /// Returns a lazy iterator over the tokens of `input`. Tokenization only
+    /// advances as items are pulled, so callers can stop early.
+    pub fn tokens<'a>(&'a self, input: &'a str) -> TokenStream<'a, K> {
+        TokenStream::new(self, input)
+    }
+
+    /// Returns a lazy iterator over zero-copy tokens of `input`. The built-in
+    /// rules produce these without allocating; convert a token with
+    /// `BorrowedToken::into_owned` when it needs to be kept around.
+    pub fn borrowed_tokens<'a>(&'a self, input: &'a str) -> BorrowedTokenStream<'a, K> {
+        BorrowedTokenStream::new(self, input)
+    }
+
+    // Adjust the tokenize method to handle the Option for default_rule
+    pub fn tokenize(&self, input: &str) -> Result<Vec<Token<K>>, Vec<TokenizationError>> {
+        let (tokens, errors) = self.tokenize_with_diagnostics(input);
+
+        if errors.is_empty() {
+            Ok(tokens)
+        } else {
+            Err(errors)
         }
+    }
+
+    /// Tokenizes the whole input and returns every token together with every
+    /// error encountered. Combined with a recovering `RecoveryPolicy`, the
+    /// tokens cover the entire input, with skipped text emitted as tokens of
+    /// kind `TokenKind::error()`.
+    pub fn tokenize_with_diagnostics(
+        &self,
+        input: &str,
+    ) -> (Vec<Token<K>>, Vec<TokenizationError>) {
+        collect(self.tokens(input))
+    }
+
+    /// Same as `tokenize`, with `state` available to the rules through
+    /// `RuleContext::state`. The caller keeps the state once the run is over.
+    pub fn tokenize_with_state<S: Any + Send>(
+        &self,
+        input: &str,
+        state: &mut S,
+    ) -> Result<Vec<Token<K>>, Vec<TokenizationError>> {
+        let (tokens, errors) = collect(self.tokens(input).with_state(state));
 
         if errors.is_empty() {
             Ok(tokens)
@@ -116,4 +463,80 @@ impl Tokenizer {
             Err(errors)
         }
     }
+
+    /// Runs the rules of `mode` against `input` and returns the match selected
+    /// by the match strategy along with the mode action of the winning rule.
+    /// The token is retyped if it is one of the rule's keywords. Errors
+    /// reported by rules are collected into `errors`.
+    pub(crate) fn match_rules<'a>(
+        &'a self,
+        mode: usize,
+        input: &'a str,
+        context: &mut RuleContext<'_, '_, K>,
+        errors: &mut Vec<TokenizationError>,
+    ) -> Option<(Match<BorrowedToken<'a, K>>, Option<&'a ModeAction>)> {
+        let mut best: Option<(Match<BorrowedToken<'a, K>>, &'a RuleEntry<K>)> = None;
+        let mode = &self.modes[mode];
+        // The automaton finds the first compiled rule to match, which is only
+        // the winner under FirstMatch, and is only searched where dispatch
+        // leaves enough compiled rules to try
+        let compiled = mode.compiled.as_ref().filter(|compiled| {
+            self.match_strategy == MatchStrategy::FirstMatch && compiled.applies_to(input)
+        });
+        // Searched when the first compiled rule comes up
+        let mut found = None;
+
+        for &index in mode.dispatch.candidates(input) {
+            let entry = &mode.rules[index];
+            let result = match compiled {
+                Some(compiled) if compiled.contains(index) => {
+                    match *found.get_or_insert_with(|| compiled.find(input)) {
+                        Some((winner, len)) if winner == index => {
+                            Ok(compiled_match(&entry.rule, input, len))
+                        }
+                        _ => Ok(None), // Another compiled rule wins, or none matches
+                    }
+                }
+                _ => entry.rule.process_borrowed(input, context),
+            };
+
+            match result {
+                Ok(Some(m)) => match self.match_strategy {
+                    MatchStrategy::FirstMatch => {
+                        best = Some((m, entry));
+                        break; // First matching rule wins
+                    }
+                    MatchStrategy::LongestMatch => {
+                        // Strictly longer only, so earlier rules win ties
+                        if best.as_ref().is_none_or(|(b, _)| m.consumed > b.consumed) {
+                            best = Some((m, entry));
+                        }
+                    }
+                },
+                Ok(None) => {}            // No match, continue to next rule
+                Err(e) => errors.push(e), // Error encountered
+            }
+        }
+
+        let (mut m, entry) = best?;
+        if let (Some(keywords), Some(token)) = (&entry.keywords, &mut m.token) {
+            keywords.retype(token, context);
+        }
+        Some((m, entry.action.as_ref()))
+    }
+}
+
+// Drains `stream`, separating the tokens from the errors.
+fn collect<K: TokenKind>(stream: TokenStream<'_, K>) -> (Vec<Token<K>>, Vec<TokenizationError>) {
+    let mut tokens = Vec::new();
+    let mut errors = Vec::new();
+
+    for item in stream {
+        match item {
+            Ok(token) => tokens.push(token),
+            Err(e) => errors.push(e),
+        }
+    }
+
+    (tokens, errors)
 }
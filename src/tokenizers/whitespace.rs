@@ -0,0 +1,43 @@
+/// `WHITESPACE_TOKEN_TYPE` is the `token_type` of the tokens emitted for runs of
+/// whitespace under `WhitespacePolicy::Emit`, when token types are `String`s.
+/// See `TokenKind::whitespace`.
+pub const WHITESPACE_TOKEN_TYPE: &str = "Whitespace";
+
+/// `NEWLINE_TOKEN_TYPE` is the `token_type` of the tokens emitted for line
+/// breaks under `WhitespacePolicy::Emit`, when token types are `String`s.
+/// See `TokenKind::newline`.
+pub const NEWLINE_TOKEN_TYPE: &str = "Newline";
+
+/// `WhitespacePolicy` decides how the tokenizer treats whitespace characters
+/// before consulting the rules.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WhitespacePolicy {
+    /// Silently skip whitespace. This is the default.
+    #[default]
+    Skip,
+
+    /// Emit each line break (`\n` or `\r\n`) as a newline token and every
+    /// other run of whitespace as a single whitespace token.
+    Emit,
+
+    /// Do not treat whitespace specially: it is passed to the rules like any
+    /// other input, so rules can match leading whitespace themselves.
+    Delegate,
+}
+
+/// Returns the length in bytes of the whitespace token starting at `input`,
+/// and whether it is a line break. `input` must start with whitespace.
+pub(crate) fn whitespace_len(input: &str, is_whitespace: impl Fn(char) -> bool) -> (usize, bool) {
+    if input.starts_with('\n') {
+        return (1, true);
+    }
+    if input.starts_with("\r\n") {
+        return (2, true);
+    }
+
+    let len = input
+        .char_indices()
+        .find(|&(i, c)| c == '\n' || input[i..].starts_with("\r\n") || !is_whitespace(c))
+        .map_or(input.len(), |(i, _)| i);
+    (len, false)
+}
@@ -0,0 +1,100 @@
+use std::borrow::Cow;
+
+use super::capture::{find_index, find_named};
+use super::{Capture, Span, Token, TokenKind};
+
+/// `BorrowedToken` is the zero-copy form of `Token`. Its `value` borrows from
+/// the input and its type names borrow from the rule that produced it, so the
+/// built-in rules emit it without allocating. Rules that build their tokens
+/// themselves (closures, callbacks) hand over owned strings instead.
+///
+/// Use `into_owned` (or `Token::from`) when the token must outlive the input
+/// or the tokenizer.
+#[derive(Debug, PartialEq, Clone)]
+pub struct BorrowedToken<'a, K: TokenKind = String> {
+    /// `token_type` is the type of the token, see `Token::token_type`.
+    pub token_type: Cow<'a, K>,
+
+    /// `token_sub_type` is the optional subtype of the token, see `Token::token_sub_type`.
+    pub token_sub_type: Option<Cow<'a, str>>,
+
+    /// `value` is the value of the token, usually a slice of the input.
+    pub value: Cow<'a, str>,
+
+    /// `line` is the line number in the source code where the token is found.
+    pub line: usize,
+
+    /// `column` is the column number in the source code where the token starts.
+    pub column: usize,
+
+    /// `span` is the range of byte offsets in the source code covered by the token.
+    pub span: Span,
+
+    /// `captures` are the parts of the token matched by capture groups, see `Token::captures`.
+    pub captures: Vec<Capture>,
+}
+
+impl<'a, K: TokenKind> BorrowedToken<'a, K> {
+    /// Creates a token that has not been positioned yet, borrowing all of its parts.
+    pub fn new(token_type: &'a K, token_sub_type: Option<&'a str>, value: &'a str) -> Self {
+        BorrowedToken {
+            token_type: Cow::Borrowed(token_type),
+            token_sub_type: token_sub_type.map(Cow::Borrowed),
+            value: Cow::Borrowed(value),
+            line: 0,
+            column: 0,
+            span: Span::default(),
+            captures: Vec::new(),
+        }
+    }
+
+    /// Returns the text of `source` the token was produced from.
+    pub fn text<'s>(&self, source: &'s str) -> &'s str {
+        self.span.text(source)
+    }
+
+    /// Returns the part of the token matched by the capture group `name`.
+    pub fn capture(&self, name: &str) -> Option<&Capture> {
+        find_named(&self.captures, name)
+    }
+
+    /// Returns the part of the token matched by the capture group numbered
+    /// `index`, starting at 1.
+    pub fn capture_at(&self, index: usize) -> Option<&Capture> {
+        find_index(&self.captures, index)
+    }
+
+    /// Converts the token into an owned `Token`, allocating only the parts
+    /// that are still borrowed.
+    pub fn into_owned(self) -> Token<K> {
+        Token {
+            token_type: self.token_type.into_owned(),
+            token_sub_type: self.token_sub_type.map(Cow::into_owned),
+            value: self.value.into_owned(),
+            line: self.line,
+            column: self.column,
+            span: self.span,
+            captures: self.captures,
+        }
+    }
+}
+
+impl<K: TokenKind> From<BorrowedToken<'_, K>> for Token<K> {
+    fn from(token: BorrowedToken<'_, K>) -> Self {
+        token.into_owned()
+    }
+}
+
+impl<K: TokenKind> From<Token<K>> for BorrowedToken<'_, K> {
+    fn from(token: Token<K>) -> Self {
+        BorrowedToken {
+            token_type: Cow::Owned(token.token_type),
+            token_sub_type: token.token_sub_type.map(Cow::Owned),
+            value: Cow::Owned(token.value),
+            line: token.line,
+            column: token.column,
+            span: token.span,
+            captures: token.captures,
+        }
+    }
+}
@@ -0,0 +1,27 @@
+use super::Span;
+
+/// `Capture` is the part of a token matched by one capture group of a regex
+/// rule, see `RegexRule::with_captures`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Capture {
+    /// `index` is the number of the group in the pattern, starting at 1.
+    pub index: usize,
+
+    /// `name` is the name of the group, if it has one.
+    pub name: Option<String>,
+
+    /// `text` is the text matched by the group.
+    pub text: String,
+
+    /// `span` is the range of byte offsets in the source code matched by the group.
+    pub span: Span,
+}
+
+// Shared lookups of `Token::capture` and `BorrowedToken::capture`
+pub(crate) fn find_named<'c>(captures: &'c [Capture], name: &str) -> Option<&'c Capture> {
+    captures.iter().find(|c| c.name.as_deref() == Some(name))
+}
+
+pub(crate) fn find_index(captures: &[Capture], index: usize) -> Option<&Capture> {
+    captures.iter().find(|c| c.index == index)
+}
@@ -1,16 +1,121 @@
 use std::{error::Error, fmt};
 
-#[derive(Debug)]
+use super::{Location, Span};
+
+/// `TokenizationError` is an error found while tokenizing an input. Every
+/// variant records where in the source it occurred.
+///
+/// Rules construct errors with a span relative to the input they were given
+/// (offset 0 is the rule's starting position) and no line or column; the
+/// tokenizer rebases the span onto the source and fills in the line and column
+/// before handing the error out.
+#[derive(Debug, PartialEq, Clone)]
 pub enum TokenizationError {
-    UnrecognizedToken(String),
-    // Define additional error types as needed.
+    /// No rule matched `lexeme`.
+    UnrecognizedToken { lexeme: String, location: Location },
+
+    /// A construct such as a string or block comment was opened but never closed.
+    /// The location points at where the construct began.
+    Unterminated {
+        construct: String,
+        location: Location,
+    },
+
+    /// An escape sequence inside a literal is not valid.
+    InvalidEscape {
+        sequence: String,
+        location: Location,
+    },
+
+    /// A rule reported an error of its own.
+    Custom { message: String, location: Location },
+
+    /// A configured limit, such as a maximum nesting depth, was exceeded.
+    LimitExceeded { limit: String, location: Location },
+}
+
+impl TokenizationError {
+    pub fn unterminated(construct: &str, span: Span) -> Self {
+        TokenizationError::Unterminated {
+            construct: construct.to_string(),
+            location: Location::new(0, 0, span),
+        }
+    }
+
+    pub fn invalid_escape(sequence: &str, span: Span) -> Self {
+        TokenizationError::InvalidEscape {
+            sequence: sequence.to_string(),
+            location: Location::new(0, 0, span),
+        }
+    }
+
+    pub fn custom(message: &str, span: Span) -> Self {
+        TokenizationError::Custom {
+            message: message.to_string(),
+            location: Location::new(0, 0, span),
+        }
+    }
+
+    pub fn limit_exceeded(limit: &str, span: Span) -> Self {
+        TokenizationError::LimitExceeded {
+            limit: limit.to_string(),
+            location: Location::new(0, 0, span),
+        }
+    }
+
+    pub fn location(&self) -> &Location {
+        match self {
+            TokenizationError::UnrecognizedToken { location, .. }
+            | TokenizationError::Unterminated { location, .. }
+            | TokenizationError::InvalidEscape { location, .. }
+            | TokenizationError::Custom { location, .. }
+            | TokenizationError::LimitExceeded { location, .. } => location,
+        }
+    }
+
+    pub fn location_mut(&mut self) -> &mut Location {
+        match self {
+            TokenizationError::UnrecognizedToken { location, .. }
+            | TokenizationError::Unterminated { location, .. }
+            | TokenizationError::InvalidEscape { location, .. }
+            | TokenizationError::Custom { location, .. }
+            | TokenizationError::LimitExceeded { location, .. } => location,
+        }
+    }
+
+    pub fn line(&self) -> usize {
+        self.location().line
+    }
+
+    pub fn column(&self) -> usize {
+        self.location().column
+    }
+
+    pub fn span(&self) -> Span {
+        self.location().span
+    }
 }
 
 impl fmt::Display for TokenizationError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            TokenizationError::UnrecognizedToken(input) => {
-                write!(f, "Unrecognized token: {}", input)
+            TokenizationError::UnrecognizedToken { lexeme, location } => {
+                write!(f, "Unrecognized token: {} at {}", lexeme, location)
+            }
+            TokenizationError::Unterminated {
+                construct,
+                location,
+            } => {
+                write!(f, "Unterminated {} starting at {}", construct, location)
+            }
+            TokenizationError::InvalidEscape { sequence, location } => {
+                write!(f, "Invalid escape sequence: {} at {}", sequence, location)
+            }
+            TokenizationError::Custom { message, location } => {
+                write!(f, "{} at {}", message, location)
+            }
+            TokenizationError::LimitExceeded { limit, location } => {
+                write!(f, "Limit exceeded: {} at {}", limit, location)
             }
         }
     }
@@ -0,0 +1,114 @@
+use std::{error::Error, fmt};
+
+/// `GrammarError` is an error found while building a tokenizer, as opposed to
+/// `TokenizationError` which is found while tokenizing an input.
+///
+/// Rules identify themselves by the mode they were added to and their index
+/// among the rules of that mode. Both are `None` when a rule is built on its
+/// own, outside of a tokenizer.
+#[derive(Debug, PartialEq, Clone)]
+pub enum GrammarError {
+    /// The pattern of a regex rule is not a valid regular expression.
+    InvalidRegex {
+        pattern: String,
+        mode: Option<String>,
+        rule_index: Option<usize>,
+        error: regex::Error,
+    },
+
+    /// The pattern of a regex rule can match the empty string, which would
+    /// make the tokenizer emit empty tokens without advancing.
+    EmptyMatch {
+        pattern: String,
+        mode: Option<String>,
+        rule_index: Option<usize>,
+    },
+
+    /// The rule `rule_index` of `mode` pushes or switches to the mode `target`,
+    /// which the tokenizer does not define.
+    UnknownMode {
+        target: String,
+        mode: String,
+        rule_index: usize,
+    },
+
+    /// The rules of a mode could not be combined into a single automaton by
+    /// `Tokenizer::compile`, for instance because it would be too large.
+    Compilation { mode: String, error: regex::Error },
+}
+
+impl GrammarError {
+    /// Records that the failing rule was being added to `mode` at `rule_index`.
+    pub(crate) fn in_rule(mut self, name: &str, index: usize) -> Self {
+        if let GrammarError::InvalidRegex {
+            mode, rule_index, ..
+        }
+        | GrammarError::EmptyMatch {
+            mode, rule_index, ..
+        } = &mut self
+        {
+            *mode = Some(name.to_string());
+            *rule_index = Some(index);
+        }
+        self
+    }
+}
+
+impl fmt::Display for GrammarError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GrammarError::InvalidRegex {
+                pattern,
+                mode,
+                rule_index,
+                error,
+            } => {
+                write!(f, "Invalid regex rule")?;
+                if let Some(index) = rule_index {
+                    write!(f, " #{}", index)?;
+                }
+                if let Some(mode) = mode {
+                    write!(f, " in mode {}", mode)?;
+                }
+                write!(f, ": {}\n{}", pattern, error)
+            }
+            GrammarError::EmptyMatch {
+                pattern,
+                mode,
+                rule_index,
+            } => {
+                write!(f, "Regex rule")?;
+                if let Some(index) = rule_index {
+                    write!(f, " #{}", index)?;
+                }
+                if let Some(mode) = mode {
+                    write!(f, " in mode {}", mode)?;
+                }
+                write!(f, " can match the empty string: {}", pattern)
+            }
+            GrammarError::UnknownMode {
+                target,
+                mode,
+                rule_index,
+            } => write!(
+                f,
+                "Rule #{} in mode {} enters unknown mode {}",
+                rule_index, mode, target
+            ),
+            GrammarError::Compilation { mode, error } => {
+                write!(f, "Cannot compile mode {}: {}", mode, error)
+            }
+        }
+    }
+}
+
+impl Error for GrammarError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            GrammarError::InvalidRegex { error, .. } | GrammarError::Compilation { error, .. } => {
+                Some(error)
+            }
+            GrammarError::EmptyMatch { .. } | GrammarError::UnknownMode { .. } => None,
+        }
+    }
+}
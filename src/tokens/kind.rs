@@ -0,0 +1,81 @@
+use std::fmt::Debug;
+
+use crate::rules::COMMENT_TOKEN_TYPE;
+use crate::tokenizers::{ERROR_TOKEN_TYPE, NEWLINE_TOKEN_TYPE, WHITESPACE_TOKEN_TYPE};
+
+/// `TokenKind` is implemented by the types a `Tokenizer` uses for
+/// `Token::token_type`. `String` implements it for dynamic grammars; static
+/// grammars usually implement it for an enum so that token types can be
+/// matched exhaustively.
+///
+/// Kinds must be `Send + Sync` so that tokenizers can be shared between
+/// threads.
+///
+/// ```
+/// use rb_tokenizer::tokens::TokenKind;
+///
+/// #[derive(Debug, Clone, Copy, PartialEq)]
+/// enum Kind {
+///     Number,
+///     Operator,
+///     Whitespace,
+///     Newline,
+///     Error,
+/// }
+///
+/// impl TokenKind for Kind {
+///     fn error() -> Self {
+///         Kind::Error
+///     }
+///
+///     fn whitespace() -> Self {
+///         Kind::Whitespace
+///     }
+///
+///     fn newline() -> Self {
+///         Kind::Newline
+///     }
+/// }
+/// ```
+pub trait TokenKind: Clone + PartialEq + Debug + Send + Sync {
+    /// Returns the kind of the tokens emitted for input skipped while
+    /// recovering from an unrecognized character.
+    fn error() -> Self;
+
+    /// Returns the kind of the whitespace tokens emitted under
+    /// `WhitespacePolicy::Emit`.
+    fn whitespace() -> Self;
+
+    /// Returns the kind of the line break tokens emitted under
+    /// `WhitespacePolicy::Emit`.
+    fn newline() -> Self;
+
+    /// Returns whether tokens of this kind are trivia, which parsers usually
+    /// ignore. `RuleContext::previous_significant` skips over them.
+    ///
+    /// Kinds with a comment variant should include it here.
+    fn is_trivia(&self) -> bool {
+        *self == Self::whitespace() || *self == Self::newline()
+    }
+}
+
+impl TokenKind for String {
+    fn error() -> Self {
+        ERROR_TOKEN_TYPE.to_string()
+    }
+
+    fn whitespace() -> Self {
+        WHITESPACE_TOKEN_TYPE.to_string()
+    }
+
+    fn newline() -> Self {
+        NEWLINE_TOKEN_TYPE.to_string()
+    }
+
+    fn is_trivia(&self) -> bool {
+        matches!(
+            self.as_str(),
+            WHITESPACE_TOKEN_TYPE | NEWLINE_TOKEN_TYPE | COMMENT_TOKEN_TYPE
+        )
+    }
+}
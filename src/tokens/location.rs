@@ -0,0 +1,47 @@
+use std::fmt;
+
+use super::Span;
+
+/// `Location` describes where in the source something happened.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct Location {
+    /// `line` is the 1-based line number where the location starts.
+    pub line: usize,
+
+    /// `column` is the 1-based column number where the location starts.
+    pub column: usize,
+
+    /// `span` is the range of byte offsets in the source covered by the location.
+    pub span: Span,
+
+    /// `mode_stack` lists the names of the lexer modes that were active, from the
+    /// outermost to the innermost. It is empty until the tokenizer places the location.
+    pub mode_stack: Vec<String>,
+}
+
+impl Location {
+    pub fn new(line: usize, column: usize, span: Span) -> Self {
+        Location {
+            line,
+            column,
+            span,
+            mode_stack: Vec::new(),
+        }
+    }
+
+    /// Returns the name of the innermost active mode, if known.
+    pub fn mode(&self) -> Option<&str> {
+        self.mode_stack.last().map(|s| s.as_str())
+    }
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}, column {}", self.line, self.column)?;
+        // Only worth mentioning once the input has left the default mode
+        if self.mode_stack.len() > 1 {
+            write!(f, " in mode {}", self.mode_stack.join(" > "))?;
+        }
+        Ok(())
+    }
+}
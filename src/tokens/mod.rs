@@ -1,5 +1,17 @@
+pub mod borrowed_token;
+pub mod capture;
 pub mod error;
+pub mod grammar_error;
+pub mod kind;
+pub mod location;
+pub mod span;
 pub mod token;
 
+pub use borrowed_token::BorrowedToken;
+pub use capture::Capture;
 pub use error::TokenizationError;
+pub use grammar_error::GrammarError;
+pub use kind::TokenKind;
+pub use location::Location;
+pub use span::Span;
 pub use token::Token;
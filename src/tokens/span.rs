@@ -0,0 +1,55 @@
+use std::ops::Range;
+
+/// `Span` is a half-open range of byte offsets (`start..end`) into the source
+/// text that was tokenized.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default)]
+pub struct Span {
+    /// `start` is the byte offset of the first byte covered by the span.
+    pub start: usize,
+
+    /// `end` is the byte offset just past the last byte covered by the span.
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize) -> Self {
+        Span { start, end }
+    }
+
+    /// Returns the number of bytes covered by the span.
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    /// Returns the smallest span covering both `self` and `other`.
+    pub fn merge(self, other: Span) -> Span {
+        Span {
+            start: self.start.min(other.start),
+            end: self.end.max(other.end),
+        }
+    }
+
+    /// Returns the text of `source` covered by the span.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the span is out of bounds or does not fall on `char`
+    /// boundaries of `source`.
+    pub fn text<'s>(&self, source: &'s str) -> &'s str {
+        &source[self.start..self.end]
+    }
+
+    pub fn range(&self) -> Range<usize> {
+        self.start..self.end
+    }
+}
+
+impl From<Range<usize>> for Span {
+    fn from(range: Range<usize>) -> Self {
+        Span::new(range.start, range.end)
+    }
+}
@@ -1,8 +1,14 @@
+use super::capture::{find_index, find_named};
+use super::{Capture, Span};
+
 /// `Token` struct represents a token in a programming language.
+///
+/// The type of the token is `K`, which defaults to `String` for dynamic
+/// grammars; see `TokenKind`.
 #[derive(Debug, PartialEq, Clone)]
-pub struct Token {
-    /// `token_type` is a string that represents the type of the token.
-    pub token_type: String,
+pub struct Token<K = String> {
+    /// `token_type` represents the type of the token.
+    pub token_type: K,
 
     /// `token_sub_type` is an optional string that represents the subtype of the token.
     /// It is `None` if the token does not have a subtype. For example, a token of type
@@ -18,4 +24,51 @@ pub struct Token {
 
     /// `column` is an unsigned integer that represents the column number in the source code where the token starts.
     pub column: usize,
+
+    /// `span` is the range of byte offsets in the source code covered by the token.
+    pub span: Span,
+
+    /// `captures` are the parts of the token matched by capture groups, for
+    /// regex rules created with `RegexRule::with_captures`. It is empty otherwise.
+    pub captures: Vec<Capture>,
+}
+
+impl Token {
+    /// Creates a token that has not been positioned yet. The tokenizer fills in
+    /// `line`, `column` and `span` when the token is emitted.
+    pub fn new(token_type: &str, token_sub_type: Option<&str>, value: &str) -> Self {
+        Token::with_kind(token_type.to_string(), token_sub_type, value)
+    }
+}
+
+impl<K> Token<K> {
+    /// Same as `Token::new`, for tokenizers with a user-defined token kind.
+    pub fn with_kind(token_type: K, token_sub_type: Option<&str>, value: &str) -> Self {
+        Token {
+            token_type,
+            token_sub_type: token_sub_type.map(|s| s.to_string()),
+            value: value.to_string(),
+            line: 0,
+            column: 0,
+            span: Span::default(),
+            captures: Vec::new(),
+        }
+    }
+
+    /// Returns the text of `source` the token was produced from. Unlike `value`,
+    /// this is always the exact input consumed by the token.
+    pub fn text<'s>(&self, source: &'s str) -> &'s str {
+        self.span.text(source)
+    }
+
+    /// Returns the part of the token matched by the capture group `name`.
+    pub fn capture(&self, name: &str) -> Option<&Capture> {
+        find_named(&self.captures, name)
+    }
+
+    /// Returns the part of the token matched by the capture group numbered
+    /// `index`, starting at 1.
+    pub fn capture_at(&self, index: usize) -> Option<&Capture> {
+        find_index(&self.captures, index)
+    }
 }
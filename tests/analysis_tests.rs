@@ -0,0 +1,147 @@
+extern crate rb_tokenizer;
+
+use rb_tokenizer::Tokenizer;
+
+fn get_tokenizer() -> Tokenizer {
+    let mut tokenizer = Tokenizer::new();
+
+    tokenizer.add_regex_rule(r"\d+", "Number", None);
+    tokenizer.add_regex_rule(r"[a-zA-Z_][a-zA-Z0-9_]*", "Identifier", None);
+    tokenizer.add_regex_rule(r"true|false", "Literal", Some("Boolean"));
+    tokenizer.add_symbol_rule("<", "Operator", Some("Less"));
+    tokenizer.add_symbol_rule("<=", "Operator", Some("LessEqual"));
+    tokenizer.add_symbol_rule("=", "Operator", Some("Assign"));
+
+    tokenizer
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::get_tokenizer;
+    use rb_tokenizer::tokenizers::GrammarIssue;
+    use rb_tokenizer::{MatchStrategy, Tokenizer};
+
+    fn shadowed(mode: &str, rule: usize, by: usize) -> GrammarIssue {
+        GrammarIssue::Shadowed {
+            mode: mode.to_string(),
+            rule,
+            by,
+        }
+    }
+
+    #[test]
+    fn first_match_reports_shadowed_rules() {
+        let tokenizer = get_tokenizer();
+
+        assert_eq!(
+            tokenizer.analyze(),
+            vec![shadowed("default", 2, 1), shadowed("default", 4, 3)]
+        );
+    }
+
+    #[test]
+    fn longest_match_reports_ties_and_overlaps() {
+        let mut tokenizer = get_tokenizer();
+        tokenizer.set_match_strategy(MatchStrategy::LongestMatch);
+
+        // `true` ties with `Identifier`, while `<=` outlasts `<`
+        assert_eq!(
+            tokenizer.analyze(),
+            vec![
+                shadowed("default", 2, 1),
+                GrammarIssue::Overlap {
+                    mode: "default".to_string(),
+                    first: 3,
+                    second: 4,
+                    example: "<=".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn overlapping_regex_rules() {
+        let mut tokenizer = Tokenizer::new();
+        tokenizer.add_regex_rule(r"[0-9]+(\.[0-9]+)?", "Number", None);
+        tokenizer.add_regex_rule(r"\.[0-9]+", "Number", Some("Fraction"));
+        tokenizer.add_regex_rule(r"[0-9a-f]+h", "Number", Some("Hex"));
+        tokenizer.add_regex_rule(r"\p{L}+", "Word", None);
+
+        let issues = tokenizer.analyze();
+        let pairs: Vec<(usize, usize, &str)> = issues
+            .iter()
+            .map(|issue| match issue {
+                GrammarIssue::Overlap {
+                    first,
+                    second,
+                    example,
+                    ..
+                } => (*first, *second, example.as_str()),
+                other => panic!("Unexpected issue: {}", other),
+            })
+            .collect();
+        assert_eq!(pairs, vec![(0, 2, "0h"), (2, 3, "ah")]);
+    }
+
+    #[test]
+    fn rules_in_different_modes_do_not_interact() {
+        let mut tokenizer = Tokenizer::new();
+        tokenizer
+            .add_symbol_rule("\"", "Quote", None)
+            .push_mode("string");
+        tokenizer.with_mode("string", |t| {
+            t.add_symbol_rule("\"", "Quote", None).pop_mode();
+            t.add_regex_rule(r#"[^"]+"#, "Text", None);
+            t.add_symbol_rule("\"", "Quote", None);
+        });
+
+        assert_eq!(tokenizer.analyze(), vec![shadowed("string", 2, 0)]);
+    }
+
+    #[test]
+    fn empty_symbols_are_reported() {
+        let mut tokenizer = Tokenizer::new();
+        tokenizer.add_symbol_rule("", "Nothing", None);
+
+        assert_eq!(
+            tokenizer.analyze(),
+            vec![GrammarIssue::EmptyMatch {
+                mode: "default".to_string(),
+                rule: 0
+            }]
+        );
+        assert!(tokenizer.analyze()[0].to_string().contains("empty string"));
+    }
+
+    #[test]
+    fn leading_anchors_still_shadow() {
+        let mut tokenizer = Tokenizer::new();
+        tokenizer.add_regex_rule(r"^[a-z]+", "Identifier", None);
+        tokenizer.add_regex_rule(r"^(?:true|false)", "Literal", None);
+
+        assert_eq!(tokenizer.analyze(), vec![shadowed("default", 1, 0)]);
+    }
+
+    #[test]
+    fn trailing_look_arounds_do_not_shadow() {
+        for strategy in [MatchStrategy::FirstMatch, MatchStrategy::LongestMatch] {
+            let mut tokenizer = Tokenizer::new();
+            tokenizer.set_match_strategy(strategy);
+            tokenizer.add_regex_rule(r"ab\b", "Word", None);
+            tokenizer.add_symbol_rule("ab", "Prefix", None);
+
+            // `\b` fails before `c`, so the symbol rule is reachable
+            let (tokens, _) = tokenizer.tokenize_with_diagnostics("abc");
+            assert_eq!(tokens[0].token_type, "Prefix");
+            assert_eq!(
+                tokenizer.analyze(),
+                vec![GrammarIssue::Overlap {
+                    mode: "default".to_string(),
+                    first: 0,
+                    second: 1,
+                    example: "ab".to_string(),
+                }]
+            );
+        }
+    }
+}
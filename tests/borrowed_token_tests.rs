@@ -0,0 +1,60 @@
+extern crate rb_tokenizer;
+
+use rb_tokenizer::tokens::Token;
+use rb_tokenizer::Tokenizer;
+
+fn get_tokenizer() -> Tokenizer {
+    let mut tokenizer = Tokenizer::new();
+
+    tokenizer.add_regex_rule(r"^\d+", "Number", None);
+    tokenizer.add_symbol_rule("+", "Operator", Some("Plus"));
+
+    // Closures build their own tokens, so these come out owned
+    tokenizer.add_closure_rule(Box::new(|input, _| {
+        if input.starts_with("pi") {
+            Ok(Some(Token::new("Number", Some("Constant"), "pi").into()))
+        } else {
+            Ok(None)
+        }
+    }));
+
+    tokenizer
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::get_tokenizer;
+    use rb_tokenizer::tokens::{BorrowedToken, Token};
+    use std::borrow::Cow;
+
+    #[test]
+    fn builtin_rules_borrow_from_input() {
+        let tokenizer = get_tokenizer();
+        let input = "12 + 345";
+        let tokens: Vec<BorrowedToken> = tokenizer
+            .borrowed_tokens(input)
+            .collect::<Result<_, _>>()
+            .expect("Tokenization failed");
+
+        for token in &tokens {
+            assert!(matches!(token.value, Cow::Borrowed(_)));
+            assert!(matches!(token.token_type, Cow::Borrowed(_)));
+            assert_eq!(token.value.as_ptr(), token.text(input).as_ptr());
+        }
+        assert_eq!(tokens[1].token_sub_type.as_deref(), Some("Plus"));
+    }
+
+    #[test]
+    fn borrowed_tokens_convert_to_owned() {
+        let tokenizer = get_tokenizer();
+        let input = "12 + pi";
+        let borrowed: Vec<Token> = tokenizer
+            .borrowed_tokens(input)
+            .map(|item| item.map(Token::from))
+            .collect::<Result<_, _>>()
+            .expect("Tokenization failed");
+
+        assert_eq!(borrowed, tokenizer.tokenize(input).unwrap());
+        assert_eq!(borrowed[2].token_sub_type.as_deref(), Some("Constant"));
+    }
+}
@@ -0,0 +1,158 @@
+extern crate rb_tokenizer;
+
+use rb_tokenizer::rules::{BlockCommentRule, LineCommentRule};
+use rb_tokenizer::Tokenizer;
+
+fn get_tokenizer() -> Tokenizer {
+    let mut tokenizer = Tokenizer::new();
+
+    // Comments go before `/` so that they are not read as divisions
+    tokenizer.add_rule(Box::new(LineCommentRule::new(
+        "//",
+        "Comment",
+        Some("Line"),
+    )));
+    tokenizer.add_rule(Box::new(BlockCommentRule::new(
+        "/*",
+        "*/",
+        "Comment",
+        Some("Block"),
+    )));
+    tokenizer.add_regex_rule(r"\d+", "Number", None);
+    tokenizer.add_regex_rule(r"[a-zA-Z_]\w*", "Identifier", None);
+    tokenizer.add_symbols(&[
+        ("+", "Operator", Some("Plus")),
+        ("*", "Operator", Some("Multiply")),
+        ("/", "Operator", Some("Divide")),
+    ]);
+
+    tokenizer
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::get_tokenizer;
+    use rb_tokenizer::rules::Match;
+    use rb_tokenizer::tokens::{Span, Token, TokenKind, TokenizationError};
+    use rb_tokenizer::WhitespacePolicy;
+
+    fn values(tokenizer: &Tokenizer, input: &str) -> Vec<String> {
+        tokenizer
+            .tokenize(input)
+            .expect("Tokenization failed")
+            .into_iter()
+            .map(|t| t.value)
+            .collect()
+    }
+
+    #[test]
+    fn line_comments_end_before_the_line_break() {
+        let tokenizer = get_tokenizer();
+
+        assert_eq!(
+            values(&tokenizer, "a / b // halve\r\nc // last"),
+            vec!["a", "/", "b", "// halve", "c", "// last"]
+        );
+
+        let tokens = tokenizer.tokenize("1 // one\n2").unwrap();
+        assert_eq!(tokens[1].token_sub_type.as_deref(), Some("Line"));
+        assert_eq!((tokens[2].line, tokens[2].column), (2, 1));
+    }
+
+    #[test]
+    fn block_comments_span_lines() {
+        let tokenizer = get_tokenizer();
+        let input = "a /* first\n * second */ * b";
+
+        let tokens = tokenizer.tokenize(input).unwrap();
+        assert_eq!(tokens[1].value, "/* first\n * second */");
+        assert_eq!(tokens[1].token_sub_type.as_deref(), Some("Block"));
+        assert_eq!(tokens[2].value, "*");
+        assert_eq!((tokens[2].line, tokens[2].column), (2, 14));
+    }
+
+    #[test]
+    fn block_comments_do_not_nest_by_default() {
+        let tokenizer = get_tokenizer();
+
+        assert_eq!(
+            values(&tokenizer, "/* a /* b */ c */"),
+            vec!["/* a /* b */", "c", "*", "/"]
+        );
+    }
+
+    #[test]
+    fn nested_block_comments() {
+        let mut tokenizer = Tokenizer::new();
+        tokenizer.add_rule(Box::new(
+            BlockCommentRule::new("/*", "*/", "Comment", None).nested(),
+        ));
+        tokenizer.add_regex_rule(r"\w+", "Identifier", None);
+
+        assert_eq!(
+            values(&tokenizer, "/* a /* b */ c */ d /**/"),
+            vec!["/* a /* b */ c */", "d", "/**/"]
+        );
+    }
+
+    #[test]
+    fn skipped_comments() {
+        let mut tokenizer = Tokenizer::new();
+        tokenizer.add_rule(Box::new(
+            LineCommentRule::new("#", "Comment", None).skipped(),
+        ));
+        tokenizer.add_rule(Box::new(
+            BlockCommentRule::new("(*", "*)", "Comment", None)
+                .nested()
+                .skipped(),
+        ));
+        tokenizer.add_regex_rule(r"\w+", "Identifier", None);
+
+        assert_eq!(
+            values(&tokenizer, "a (* b (* c *) *) d # e\nf"),
+            vec!["a", "d", "f"]
+        );
+    }
+
+    #[test]
+    fn unterminated_block_comment_points_at_its_start() {
+        let tokenizer = get_tokenizer();
+        let input = "a +\n  /* never\n closed";
+
+        let (tokens, errors) = tokenizer.tokenize_with_diagnostics(input);
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(
+            &errors[0],
+            TokenizationError::Unterminated { construct, .. } if construct == "block comment"
+        ));
+        assert_eq!(errors[0].span(), Span::new(6, 8));
+        assert_eq!((errors[0].line(), errors[0].column()), (2, 3));
+        assert_eq!(
+            errors[0].to_string(),
+            "Unterminated block comment starting at line 2, column 3"
+        );
+
+        // The rest of the input is the comment
+        assert_eq!(tokens.len(), 3);
+        assert_eq!(tokens[2].value, "/* never\n closed");
+    }
+
+    #[test]
+    fn comments_are_trivia() {
+        assert!("Comment".to_string().is_trivia());
+
+        let mut tokenizer = get_tokenizer();
+        tokenizer.set_whitespace_policy(WhitespacePolicy::Emit);
+        tokenizer.add_closure_rule(Box::new(|input, context| {
+            let previous = context.previous_significant().map(|t| t.value.to_string());
+            Ok(input
+                .starts_with('?')
+                .then(|| Token::new("Query", None, &previous.unwrap()))
+                .map(|token| Match::new(1, token)))
+        }));
+
+        let tokens = tokenizer.tokenize("x /* c */ // d\n?").unwrap();
+        assert_eq!(tokens.last().unwrap().value, "x");
+    }
+}
@@ -0,0 +1,180 @@
+extern crate rb_tokenizer;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use rb_tokenizer::rules::{FirstBytes, Match, RegexRule, Rule, RuleContext, SymbolRule};
+use rb_tokenizer::tokens::{Token, TokenizationError};
+use rb_tokenizer::Tokenizer;
+
+// Wraps a rule to count how often the tokenizer runs it
+struct Counted {
+    rule: Box<dyn Rule>,
+    calls: Arc<AtomicUsize>,
+    dispatch: bool,
+}
+
+impl Rule for Counted {
+    fn process(
+        &self,
+        input: &str,
+        context: &mut RuleContext<'_, '_>,
+    ) -> Result<Option<Match<Token>>, TokenizationError> {
+        self.calls.fetch_add(1, Ordering::Relaxed);
+        self.rule.process(input, context)
+    }
+
+    fn first_bytes(&self) -> FirstBytes {
+        if self.dispatch {
+            self.rule.first_bytes()
+        } else {
+            FirstBytes::any()
+        }
+    }
+}
+
+fn get_tokenizer(dispatch: bool) -> (Tokenizer, Arc<AtomicUsize>) {
+    let mut tokenizer = Tokenizer::new();
+    let calls = Arc::new(AtomicUsize::new(0));
+    let mut add = |rule: Box<dyn Rule>| {
+        tokenizer.add_rule(Box::new(Counted {
+            rule,
+            calls: calls.clone(),
+            dispatch,
+        }));
+    };
+
+    add(Box::new(RegexRule::new(r"\d+(\.\d+)?", "Number", None)));
+    add(Box::new(RegexRule::new(r#""[^"]*""#, "String", None)));
+    add(Box::new(RegexRule::new(r"true|false", "Boolean", None)));
+    add(Box::new(RegexRule::new(
+        r"[a-zA-Z_]\w*",
+        "Identifier",
+        None,
+    )));
+    for symbol in ["==", "!=", "<=", ">=", "&&", "||", "|", ":"] {
+        add(Box::new(SymbolRule::new(symbol, "Operator", None)));
+    }
+    for symbol in ["+", "-", "*", "/", "%", "<", ">", "!", "="] {
+        add(Box::new(SymbolRule::new(symbol, "Operator", None)));
+    }
+    for symbol in ["(", ")", "[", "]", "{", "}", ",", "."] {
+        add(Box::new(SymbolRule::new(symbol, "Punctuation", None)));
+    }
+
+    (tokenizer, calls)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::get_tokenizer;
+
+    const INPUT: &str =
+        r#"user.age >= 18 && (user.role == "admin" || flags[0] != false) |map: x * 2.5"#;
+
+    #[test]
+    fn dispatch_runs_fewer_rules_with_the_same_result() {
+        let (tokenizer, calls) = get_tokenizer(true);
+        let (undispatched, all_calls) = get_tokenizer(false);
+
+        let tokens = tokenizer.tokenize(INPUT).expect("Tokenization failed");
+        assert_eq!(tokens, undispatched.tokenize(INPUT).unwrap());
+
+        let calls = calls.load(Ordering::Relaxed);
+        let all_calls = all_calls.load(Ordering::Relaxed);
+        assert!(
+            calls * 5 < all_calls,
+            "{} calls with dispatch, {} without",
+            calls,
+            all_calls
+        );
+    }
+
+    #[test]
+    fn rules_are_only_run_on_their_first_bytes() {
+        let (tokenizer, calls) = get_tokenizer(true);
+
+        // Only `Number` can start with a digit
+        tokenizer.tokenize("42").unwrap();
+        assert_eq!(calls.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn compiled_rules_and_dispatch_run_custom_rules_as_often() {
+        // Enough keyword rules start with `i` for the compiled automaton to
+        // pick the winner there, before the custom identifier rule
+        let get_tokenizer = || {
+            let mut tokenizer = Tokenizer::new();
+            let calls = Arc::new(AtomicUsize::new(0));
+            for keyword in ["if", "in", "impl", "import"] {
+                tokenizer.add_regex_rule(&format!(r"{}\b", keyword), "Keyword", None);
+            }
+            tokenizer.add_rule(Box::new(Counted {
+                rule: Box::new(RegexRule::new(r"[a-z]+", "Identifier", None)),
+                calls: calls.clone(),
+                dispatch: true,
+            }));
+            tokenizer.add_regex_rule(r"\d+", "Number", None);
+            (tokenizer, calls)
+        };
+        let (tokenizer, calls) = get_tokenizer();
+        let (mut compiled, compiled_calls) = get_tokenizer();
+        compiled.compile().expect("Compilation failed");
+
+        let input = "if x in items import impl inner 42";
+        let tokens = compiled.tokenize(input).expect("Tokenization failed");
+        assert_eq!(tokens, tokenizer.tokenize(input).unwrap());
+
+        // Only `x`, `items` and `inner` are left to the identifier rule
+        assert_eq!(compiled_calls.load(Ordering::Relaxed), 3);
+        assert_eq!(calls.load(Ordering::Relaxed), 3);
+    }
+
+    #[test]
+    fn closure_rules_are_always_run() {
+        let mut tokenizer = Tokenizer::new();
+        tokenizer.add_closure_rule(Box::new(|input, _context| {
+            Ok(input
+                .starts_with("#!")
+                .then(|| Token::new("Shebang", None, input.lines().next().unwrap()).into()))
+        }));
+        tokenizer.add_symbol_rule("#", "Hash", None);
+
+        let tokens = tokenizer.tokenize("#!run\n#").unwrap();
+        let types: Vec<_> = tokens.iter().map(|t| t.token_type.as_str()).collect();
+        assert_eq!(types, vec!["Shebang", "Hash"]);
+    }
+
+    #[test]
+    fn regex_first_bytes() {
+        let first_bytes = |pattern| RegexRule::<String>::new(pattern, "T", None).first_bytes();
+
+        let digits = first_bytes(r"\d+");
+        assert!(digits.contains(b'0') && digits.contains(b'9'));
+        assert!(!digits.contains(b'a'));
+
+        // Optional prefixes and look-arounds let the next item start the match
+        let optional = first_bytes(r"\b-?\d");
+        assert!(optional.contains(b'-') && optional.contains(b'5'));
+        assert!(!optional.contains(b'+'));
+
+        // The Kelvin sign matches `k` case-insensitively
+        let kelvin = first_bytes(r"(?i)k");
+        assert!(kelvin.contains(b'k') && kelvin.contains(b'K'));
+        assert!(kelvin.contains("\u{212A}".as_bytes()[0]));
+
+        let greek = first_bytes(r"\p{Greek}+");
+        assert!(greek.contains("α".as_bytes()[0]));
+        assert!(!greek.contains(b'a'));
+    }
+
+    #[test]
+    fn symbol_first_bytes() {
+        let rule = SymbolRule::<String>::new("<=", "Operator", None);
+        assert_eq!(rule.first_bytes(), FirstBytes::from_iter([b'<']));
+        assert!(SymbolRule::<String>::new("", "Nothing", None)
+            .first_bytes()
+            .is_any());
+    }
+}
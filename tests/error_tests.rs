@@ -0,0 +1,72 @@
+extern crate rb_tokenizer;
+
+use rb_tokenizer::tokens::{Span, TokenizationError};
+use rb_tokenizer::Tokenizer;
+
+fn get_tokenizer() -> Tokenizer {
+    let mut tokenizer = Tokenizer::new();
+
+    tokenizer.add_regex_rule(r"^\d+", "Number", None);
+    tokenizer.add_symbol_rule("+", "Operator", Some("Plus"));
+
+    // `#!` is reserved: report the `!` as an error relative to the rule's input
+    tokenizer.add_closure_rule(Box::new(|input, _| {
+        if input.starts_with("#!") {
+            Err(TokenizationError::custom(
+                "reserved directive",
+                Span::new(1, 2),
+            ))
+        } else {
+            Ok(None)
+        }
+    }));
+
+    tokenizer
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::get_tokenizer;
+    use rb_tokenizer::tokens::{Location, Span, TokenizationError};
+
+    #[test]
+    fn unrecognized_token_reports_lexeme_and_position() {
+        let tokenizer = get_tokenizer();
+        let errors = tokenizer.tokenize("1 +\n 2 @ 3").unwrap_err();
+
+        assert_eq!(
+            errors,
+            vec![TokenizationError::UnrecognizedToken {
+                lexeme: "@".to_string(),
+                location: Location {
+                    mode_stack: vec!["default".to_string()],
+                    ..Location::new(2, 4, Span::new(7, 8))
+                },
+            }]
+        );
+        assert_eq!(
+            errors[0].to_string(),
+            "Unrecognized token: @ at line 2, column 4"
+        );
+    }
+
+    #[test]
+    fn rule_errors_are_rebased_onto_the_source() {
+        let tokenizer = get_tokenizer();
+        let errors = tokenizer.tokenize("1 + #!").unwrap_err();
+
+        match &errors[0] {
+            TokenizationError::Custom { message, location } => {
+                assert_eq!(message, "reserved directive");
+                assert_eq!(
+                    (location.line, location.column, location.span),
+                    (1, 6, Span::new(5, 6))
+                );
+            }
+            e => panic!("unexpected error: {:?}", e),
+        }
+        // The `#` itself is still unrecognized afterwards
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[1].column(), 5);
+    }
+}
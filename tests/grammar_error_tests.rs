@@ -0,0 +1,103 @@
+extern crate rb_tokenizer;
+
+use rb_tokenizer::Tokenizer;
+
+// A grammar whose patterns come from configuration, some of them broken
+fn get_tokenizer() -> Tokenizer {
+    let mut tokenizer = Tokenizer::new();
+
+    let _ = tokenizer.try_add_regex_rule(r"^\d+", "Number", None);
+    let _ = tokenizer.try_add_regex_rule(r"^[a-z", "Identifier", None);
+    tokenizer.add_symbol_rule("+", "Operator", Some("Plus"));
+    tokenizer.with_mode("string", |t| {
+        let _ = t.try_add_regex_rule(r"^[^']+", "Text", None);
+        let _ = t.try_add_regex_rule(r"^\p{Unknown}", "Text", None);
+    });
+
+    tokenizer
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::get_tokenizer;
+    use rb_tokenizer::rules::RegexRule;
+    use rb_tokenizer::tokens::GrammarError;
+    use rb_tokenizer::tokenizers::GrammarIssue;
+    use rb_tokenizer::Tokenizer;
+    use std::error::Error;
+
+    #[test]
+    fn invalid_patterns_do_not_panic() {
+        let mut tokenizer = Tokenizer::new();
+        let error = tokenizer
+            .try_add_regex_rule(r"^(\d+", "Number", None)
+            .err()
+            .expect("Pattern should be rejected");
+
+        match &error {
+            GrammarError::InvalidRegex {
+                pattern,
+                mode,
+                rule_index,
+                ..
+            } => {
+                assert_eq!(pattern, r"^(\d+");
+                assert_eq!(mode.as_deref(), Some("default"));
+                assert_eq!(*rule_index, Some(0));
+            }
+            other => panic!("Unexpected error: {:?}", other),
+        }
+        assert!(error.source().is_some());
+        assert!(error
+            .to_string()
+            .starts_with("Invalid regex rule #0 in mode default"));
+
+        assert!(RegexRule::<String>::try_new(r"^)", "Paren", None).is_err());
+        assert!(RegexRule::<String>::try_new(r"^\)", "Paren", None).is_ok());
+    }
+
+    #[test]
+    fn compile_reports_every_error() {
+        let mut tokenizer = get_tokenizer();
+        assert_eq!(tokenizer.grammar_errors().len(), 2);
+
+        let errors = tokenizer.compile().unwrap_err();
+        let rules: Vec<(Option<&str>, Option<usize>)> = errors
+            .iter()
+            .map(|e| match e {
+                GrammarError::InvalidRegex {
+                    mode, rule_index, ..
+                } => (mode.as_deref(), *rule_index),
+                other => panic!("Unexpected error: {:?}", other),
+            })
+            .collect();
+        assert_eq!(
+            rules,
+            vec![(Some("default"), Some(1)), (Some("string"), Some(1))]
+        );
+
+        // The valid rules are still usable
+        let result = tokenizer.tokenize("1 + 2").expect("Tokenization failed");
+        assert_eq!(result.len(), 3);
+    }
+
+    #[test]
+    fn rejected_rules_keep_their_index() {
+        let mut tokenizer = Tokenizer::new();
+        let _ = tokenizer.try_add_regex_rule(r"^[a-z", "Identifier", None);
+        tokenizer.add_symbol_rule("+", "Operator", Some("Plus"));
+        tokenizer.add_symbol_rule("+", "Operator", Some("Plus"));
+
+        // The rejected rule keeps index 0, so the symbol rules are #1 and #2
+        let errors = tokenizer.compile().unwrap_err();
+        assert!(errors[0].to_string().starts_with("Invalid regex rule #0"));
+        assert_eq!(
+            tokenizer.analyze(),
+            vec![GrammarIssue::Shadowed {
+                mode: "default".into(),
+                rule: 2,
+                by: 1,
+            }]
+        );
+    }
+}
@@ -0,0 +1,176 @@
+extern crate rb_tokenizer;
+
+use rb_tokenizer::rules::{KeywordTable, RuleContext};
+use rb_tokenizer::Tokenizer;
+
+fn get_tokenizer() -> Tokenizer {
+    let mut tokenizer = Tokenizer::new();
+
+    tokenizer.add_regex_rule(r"\d+", "Number", None);
+    let keywords = KeywordTable::new()
+        .with("let", "Keyword", Some("Let"))
+        .with("if", "Keyword", Some("If"))
+        .with("else", "Keyword", Some("Else"))
+        .with("true", "Literal", Some("Boolean"))
+        .with("false", "Literal", Some("Boolean"))
+        .with("null", "Literal", Some("Null"))
+        // `type` is a keyword at the start of a statement only
+        .with_contextual(
+            "type",
+            "Keyword",
+            Some("Type"),
+            |context: &mut RuleContext| {
+                context
+                    .previous_significant()
+                    .is_none_or(|t| t.token_type.as_str() == "Semicolon")
+            },
+        );
+    tokenizer
+        .add_regex_rule(r"[a-zA-Z_]\w*", "Identifier", None)
+        .keywords(keywords);
+    tokenizer.add_symbol_rule("=", "Operator", Some("Assign"));
+    tokenizer.add_symbol_rule(";", "Semicolon", None);
+
+    tokenizer
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::get_tokenizer;
+    use rb_tokenizer::MatchStrategy;
+
+    fn classify(tokenizer: &Tokenizer, input: &str) -> Vec<(String, Option<String>)> {
+        tokenizer
+            .tokenize(input)
+            .expect("Tokenization failed")
+            .into_iter()
+            .map(|t| (t.token_type, t.token_sub_type))
+            .collect()
+    }
+
+    fn token(token_type: &str, sub_type: Option<&str>) -> (String, Option<String>) {
+        (token_type.to_string(), sub_type.map(|s| s.to_string()))
+    }
+
+    #[test]
+    fn keywords_are_retyped() {
+        let tokenizer = get_tokenizer();
+
+        assert_eq!(
+            classify(&tokenizer, "let x = true"),
+            vec![
+                token("Keyword", Some("Let")),
+                token("Identifier", None),
+                token("Operator", Some("Assign")),
+                token("Literal", Some("Boolean")),
+            ]
+        );
+    }
+
+    #[test]
+    fn words_containing_keywords_stay_identifiers() {
+        let tokenizer = get_tokenizer();
+
+        let tokens = tokenizer.tokenize("iffy nullable letter If").unwrap();
+        assert!(tokens.iter().all(|t| t.token_type == "Identifier"));
+    }
+
+    #[test]
+    fn contextual_keywords() {
+        let tokenizer = get_tokenizer();
+
+        assert_eq!(
+            classify(&tokenizer, "type t = type; type = 1"),
+            vec![
+                token("Keyword", Some("Type")),
+                token("Identifier", None),
+                token("Operator", Some("Assign")),
+                token("Identifier", None),
+                token("Semicolon", None),
+                token("Keyword", Some("Type")),
+                token("Operator", Some("Assign")),
+                token("Number", None),
+            ]
+        );
+    }
+
+    #[test]
+    fn case_insensitive_keywords() {
+        let mut tokenizer = Tokenizer::new();
+        tokenizer
+            .add_regex_rule(r"[a-zA-Z_]\w*", "Identifier", None)
+            .keywords(
+                KeywordTable::new()
+                    .with("SELECT", "Keyword", Some("Select"))
+                    .case_insensitive()
+                    .with("From", "Keyword", Some("From")),
+            );
+
+        let tokens = tokenizer.tokenize("select Name FROM users").unwrap();
+        let types: Vec<_> = tokens.iter().map(|t| t.token_type.as_str()).collect();
+        assert_eq!(
+            types,
+            vec!["Keyword", "Identifier", "Keyword", "Identifier"]
+        );
+        // The value keeps the case of the input
+        assert_eq!(tokens[2].value, "FROM");
+    }
+
+    #[test]
+    fn keywords_apply_to_the_winning_rule_only() {
+        let mut tokenizer = get_tokenizer();
+        tokenizer.set_match_strategy(MatchStrategy::LongestMatch);
+        tokenizer.add_regex_rule(r"[a-z]+:[a-z]+", "Qualified", None);
+
+        assert_eq!(
+            classify(&tokenizer, "if:else if"),
+            vec![token("Qualified", None), token("Keyword", Some("If"))]
+        );
+    }
+
+    #[test]
+    fn borrowed_tokens_are_retyped() {
+        let tokenizer = get_tokenizer();
+
+        let tokens: Vec<_> = tokenizer
+            .borrowed_tokens("else null")
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(tokens[0].token_type.as_str(), "Keyword");
+        assert_eq!(tokens[1].token_sub_type.as_deref(), Some("Null"));
+    }
+
+    #[test]
+    fn predicates_can_read_the_state() {
+        let mut tokenizer = Tokenizer::new();
+        tokenizer
+            .add_regex_rule(r"[a-z]+", "Identifier", None)
+            .keywords(
+                KeywordTable::new().with_contextual("await", "Keyword", None, |context| {
+                    context.state::<bool>().is_some_and(|in_async| *in_async)
+                }),
+            );
+
+        let mut in_async = true;
+        let tokens = tokenizer
+            .tokenize_with_state("await", &mut in_async)
+            .unwrap();
+        assert_eq!(tokens[0].token_type, "Keyword");
+
+        let tokens = tokenizer.tokenize("await").unwrap();
+        assert_eq!(tokens[0].token_type, "Identifier");
+    }
+
+    #[test]
+    fn classify_outside_a_tokenizer() {
+        let table = KeywordTable::<String>::new()
+            .with("fn", "Keyword", None)
+            .with_contextual("union", "Keyword", None, |_| false);
+        let mut context = RuleContext::new("fn union");
+
+        assert!(table.classify("fn", &mut context).is_some());
+        assert!(table.classify("union", &mut context).is_none());
+        assert!(table.classify("struct", &mut context).is_none());
+    }
+}
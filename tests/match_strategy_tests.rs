@@ -0,0 +1,60 @@
+extern crate rb_tokenizer;
+
+use rb_tokenizer::{MatchStrategy, Tokenizer};
+
+fn get_tokenizer(strategy: MatchStrategy) -> Tokenizer {
+    let mut tokenizer = Tokenizer::new();
+    tokenizer.set_match_strategy(strategy);
+
+    // Prefixes deliberately registered before the longer operators
+    tokenizer.add_symbol_rule("<", "Operator", Some("LessThan"));
+    tokenizer.add_symbol_rule("=", "Operator", Some("Assign"));
+    tokenizer.add_symbol_rule("<=", "Operator", Some("LessThanOrEqual"));
+    tokenizer.add_symbol_rule("<<", "Operator", Some("BitwiseLeftShift"));
+
+    tokenizer.add_regex_rule(r"^(true|false)", "Literal", None);
+    tokenizer.add_regex_rule(r"^[a-zA-Z_][a-zA-Z0-9_]*", "Identifier", None);
+
+    tokenizer
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::get_tokenizer;
+    use rb_tokenizer::MatchStrategy;
+
+    #[test]
+    fn first_match_uses_rule_order() {
+        let tokenizer = get_tokenizer(MatchStrategy::FirstMatch);
+        let result = tokenizer.tokenize("a <= b").expect("Tokenization failed");
+
+        let values: Vec<&str> = result.iter().map(|t| t.value.as_str()).collect();
+        assert_eq!(values, vec!["a", "<", "=", "b"]);
+    }
+
+    #[test]
+    fn longest_match_prefers_longer_symbols() {
+        let tokenizer = get_tokenizer(MatchStrategy::LongestMatch);
+        let result = tokenizer
+            .tokenize("a <= b << c")
+            .expect("Tokenization failed");
+
+        let values: Vec<&str> = result.iter().map(|t| t.value.as_str()).collect();
+        assert_eq!(values, vec!["a", "<=", "b", "<<", "c"]);
+        assert_eq!(result[1].token_sub_type.as_deref(), Some("LessThanOrEqual"));
+    }
+
+    #[test]
+    fn longest_match_breaks_ties_by_rule_order() {
+        let tokenizer = get_tokenizer(MatchStrategy::LongestMatch);
+        let result = tokenizer
+            .tokenize("true trueish")
+            .expect("Tokenization failed");
+
+        // `true` is matched by both rules with the same length, so the earlier
+        // Literal rule wins; `trueish` is longer as an Identifier.
+        assert_eq!(result[0].token_type, "Literal");
+        assert_eq!(result[1].token_type, "Identifier");
+        assert_eq!(result[1].value, "trueish");
+    }
+}
@@ -0,0 +1,144 @@
+extern crate rb_tokenizer;
+
+use rb_tokenizer::Tokenizer;
+
+// Backtick strings with `${ ... }` interpolation, which may nest further strings
+fn get_tokenizer() -> Tokenizer {
+    let mut tokenizer = Tokenizer::new();
+
+    tokenizer
+        .add_symbol_rule("`", "Backtick", Some("Open"))
+        .push_mode("template");
+    tokenizer.add_symbol_rule("+", "Operator", Some("Plus"));
+    tokenizer.add_regex_rule(r"^[a-zA-Z_][a-zA-Z0-9_]*", "Identifier", None);
+
+    tokenizer.with_mode("template", |t| {
+        t.add_symbol_rule("`", "Backtick", Some("Close")).pop_mode();
+        t.add_symbol_rule("${", "Interpolation", Some("Open"))
+            .push_mode("expr");
+        t.add_regex_rule(r"^([^`$\\]|\\.|\$[^{])+", "TemplateText", None);
+    });
+
+    tokenizer.with_mode("expr", |t| {
+        t.add_symbol_rule("}", "Interpolation", Some("Close"))
+            .pop_mode();
+        t.add_symbol_rule("`", "Backtick", Some("Open"))
+            .push_mode("template");
+        t.add_symbol_rule("+", "Operator", Some("Plus"));
+        t.add_regex_rule(r"^[a-zA-Z_][a-zA-Z0-9_]*", "Identifier", None);
+    });
+
+    tokenizer
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::get_tokenizer;
+    use rb_tokenizer::rules::Match;
+    use rb_tokenizer::tokens::GrammarError;
+    use rb_tokenizer::Tokenizer;
+
+    #[test]
+    fn interpolation_switches_rule_sets() {
+        let tokenizer = get_tokenizer();
+        let result = tokenizer
+            .tokenize("`Hi ${name + `x${y}`}!` + z")
+            .expect("Tokenization failed");
+
+        let types: Vec<&str> = result.iter().map(|t| t.token_type.as_str()).collect();
+        assert_eq!(
+            types,
+            vec![
+                "Backtick",
+                "TemplateText",
+                "Interpolation",
+                "Identifier",
+                "Operator",
+                "Backtick",
+                "TemplateText",
+                "Interpolation",
+                "Identifier",
+                "Interpolation",
+                "Backtick",
+                "Interpolation",
+                "TemplateText",
+                "Backtick",
+                "Operator",
+                "Identifier",
+            ]
+        );
+    }
+
+    #[test]
+    fn mode_stack_is_tracked_and_reported() {
+        let tokenizer = get_tokenizer();
+        let mut stream = tokenizer.tokens("`a ${b @");
+
+        stream.next();
+        assert_eq!(stream.mode(), "template");
+        stream.nth(1);
+        assert_eq!(stream.mode_stack(), vec!["default", "template", "expr"]);
+
+        let errors: Vec<_> = stream.filter_map(Result::err).collect();
+        assert_eq!(errors[0].location().mode(), Some("expr"));
+        assert_eq!(
+            errors[0].to_string(),
+            "Unrecognized token: @ at line 1, column 8 in mode default > template > expr"
+        );
+    }
+
+    #[test]
+    fn skipped_matches_change_mode() {
+        let mut tokenizer = Tokenizer::new();
+        tokenizer
+            .add_closure_rule(Box::new(|input, _| {
+                Ok(input.starts_with("%raw").then(|| Match::skip(4)))
+            }))
+            .switch_mode("raw");
+        tokenizer.add_regex_rule(r"^\d+", "Number", None);
+        tokenizer.with_mode("raw", |t| {
+            t.add_regex_rule(r"^\S+", "Raw", None);
+        });
+
+        let result = tokenizer.tokenize("1 %raw 2").expect("Tokenization failed");
+        let types: Vec<&str> = result.iter().map(|t| t.token_type.as_str()).collect();
+        assert_eq!(types, vec!["Number", "Raw"]);
+    }
+
+    #[test]
+    fn unknown_modes_are_grammar_errors() {
+        let mut tokenizer = get_tokenizer();
+        tokenizer.add_symbol_rule("{", "Brace", None).push_mode("block");
+        tokenizer.with_mode("template", |t| {
+            t.add_symbol_rule("#", "Hash", None).switch_mode("tempalte");
+        });
+        assert!(tokenizer.grammar_errors().is_empty());
+
+        let errors = tokenizer.compile().unwrap_err();
+        assert_eq!(
+            errors,
+            vec![
+                GrammarError::UnknownMode {
+                    target: "block".into(),
+                    mode: "default".into(),
+                    rule_index: 3,
+                },
+                GrammarError::UnknownMode {
+                    target: "tempalte".into(),
+                    mode: "template".into(),
+                    rule_index: 3,
+                },
+            ]
+        );
+        assert_eq!(
+            errors[0].to_string(),
+            "Rule #3 in mode default enters unknown mode block"
+        );
+
+        // Modes may be defined after the rules entering them
+        tokenizer.with_mode("block", |t| {
+            t.add_symbol_rule("}", "Brace", None).pop_mode();
+        });
+        assert_eq!(tokenizer.compile().unwrap_err().len(), 1);
+    }
+}
@@ -0,0 +1,63 @@
+extern crate rb_tokenizer;
+
+use rb_tokenizer::{RecoveryPolicy, Tokenizer};
+
+fn get_tokenizer(policy: RecoveryPolicy) -> Tokenizer {
+    let mut tokenizer = Tokenizer::new();
+    tokenizer.set_recovery_policy(policy);
+
+    tokenizer.add_regex_rule(r"^\d+", "Number", None);
+    tokenizer.add_regex_rule(r"^[a-zA-Z_][a-zA-Z0-9_]*", "Identifier", None);
+    tokenizer.add_symbol_rule("+", "Operator", Some("Plus"));
+    tokenizer.add_symbol_rule(";", "Semicolon", None);
+
+    tokenizer
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::get_tokenizer;
+    use rb_tokenizer::tokenizers::ERROR_TOKEN_TYPE;
+    use rb_tokenizer::tokens::Span;
+    use rb_tokenizer::RecoveryPolicy;
+
+    #[test]
+    fn halt_stops_at_first_unrecognized_character() {
+        let tokenizer = get_tokenizer(RecoveryPolicy::Halt);
+        let (tokens, errors) = tokenizer.tokenize_with_diagnostics("1 + @ 2 # 3");
+
+        assert_eq!(tokens.len(), 2);
+        assert_eq!(errors.len(), 1);
+    }
+
+    #[test]
+    fn skip_char_reports_every_problem() {
+        let tokenizer = get_tokenizer(RecoveryPolicy::SkipChar);
+        let input = "1 + @ 2 # 3";
+        let (tokens, errors) = tokenizer.tokenize_with_diagnostics(input);
+
+        let values: Vec<&str> = tokens.iter().map(|t| t.value.as_str()).collect();
+        assert_eq!(values, vec!["1", "+", "@", "2", "#", "3"]);
+        assert_eq!(errors.len(), 2);
+
+        assert_eq!(tokens[2].token_type, ERROR_TOKEN_TYPE);
+        assert_eq!(tokens[2].span, Span::new(4, 5));
+        assert_eq!(tokens[4].column, 9);
+        assert!(tokenizer.tokenize(input).is_err());
+    }
+
+    #[test]
+    fn skip_to_whitespace_and_sync_set() {
+        let tokenizer = get_tokenizer(RecoveryPolicy::SkipToWhitespace);
+        let (tokens, _) = tokenizer.tokenize_with_diagnostics("a @@é b");
+        let values: Vec<&str> = tokens.iter().map(|t| t.value.as_str()).collect();
+        assert_eq!(values, vec!["a", "@@é", "b"]);
+        assert_eq!(tokens[2].column, 7);
+
+        let tokenizer = get_tokenizer(RecoveryPolicy::SkipToSync(vec![';']));
+        let (tokens, errors) = tokenizer.tokenize_with_diagnostics("a @ b c; d");
+        let values: Vec<&str> = tokens.iter().map(|t| t.value.as_str()).collect();
+        assert_eq!(values, vec!["a", "@ b c", ";", "d"]);
+        assert_eq!(errors.len(), 1);
+    }
+}
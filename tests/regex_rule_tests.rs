@@ -0,0 +1,152 @@
+extern crate rb_tokenizer;
+
+use rb_tokenizer::Tokenizer;
+
+// No pattern starts with `^`
+fn get_tokenizer() -> Tokenizer {
+    let mut tokenizer = Tokenizer::new();
+
+    tokenizer.add_regex_rule(r"\d+", "Number", None);
+    tokenizer.add_regex_rule(r"[a-zA-Z_][a-zA-Z0-9_]*", "Identifier", None);
+    tokenizer.add_regex_rule(
+        r"(?x)
+            \+ | -   # additive
+          | \* | /   # multiplicative",
+        "Operator",
+        None,
+    );
+
+    tokenizer
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::get_tokenizer;
+    use rb_tokenizer::rules::RegexRule;
+    use rb_tokenizer::tokens::{GrammarError, Span};
+    use rb_tokenizer::Tokenizer;
+
+    #[test]
+    fn patterns_are_anchored_at_the_current_position() {
+        let tokenizer = get_tokenizer();
+        let input = "x * 42 - y";
+        let result = tokenizer.tokenize(input).expect("Tokenization failed");
+
+        let values: Vec<&str> = result.iter().map(|t| t.value.as_str()).collect();
+        assert_eq!(values, vec!["x", "*", "42", "-", "y"]);
+        for token in &result {
+            assert_eq!(token.text(input), token.value);
+        }
+
+        // `@` is not skipped over to reach the number after it
+        let errors = tokenizer.tokenize("@1").unwrap_err();
+        assert_eq!(errors[0].span(), Span::new(0, 1));
+    }
+
+    #[test]
+    fn original_pattern_is_kept() {
+        let rule = RegexRule::<String>::new(r"\d+", "Number", None);
+        assert_eq!(rule.source(), r"\d+");
+        assert!(!rule.pattern().is_match("a1"));
+    }
+
+    #[test]
+    fn empty_matching_patterns_are_rejected() {
+        let mut tokenizer = Tokenizer::new();
+
+        for pattern in [r"\d*", r"(a|)", r"\b", r"^"] {
+            match tokenizer.try_add_regex_rule(pattern, "Empty", None) {
+                Err(GrammarError::EmptyMatch { pattern: p, .. }) => assert_eq!(p, pattern),
+                other => panic!("{} should be rejected, got {:?}", pattern, other.err()),
+            }
+        }
+        assert!(tokenizer.try_add_regex_rule(r"\d+", "Number", None).is_ok());
+        assert_eq!(tokenizer.compile().unwrap_err().len(), 4);
+    }
+
+    #[test]
+    #[should_panic]
+    fn add_regex_rule_panics_on_empty_matches() {
+        Tokenizer::new().add_regex_rule(r"\s*", "Space", None);
+    }
+
+    #[test]
+    fn sub_type_is_emitted() {
+        let mut tokenizer = Tokenizer::new();
+        tokenizer.add_regex_rule(r"\d+\.\d+", "Number", Some("Float"));
+
+        let owned = tokenizer.tokenize("1.5").expect("Tokenization failed");
+        assert_eq!(owned[0].token_sub_type.as_deref(), Some("Float"));
+
+        let borrowed = tokenizer.borrowed_tokens("1.5").next().unwrap().unwrap();
+        assert_eq!(borrowed.token_sub_type.as_deref(), Some("Float"));
+    }
+
+    #[test]
+    fn sub_type_from_named_groups() {
+        let mut tokenizer = Tokenizer::new();
+        tokenizer.add_regex(
+            RegexRule::new(
+                r"(?<Hex>0x[0-9a-fA-F]+)|(?<Float>\d+\.\d+(?<Exponent>e\d+)?)|(?<Integer>\d+)",
+                "Number",
+                None,
+            )
+            .with_group_subtypes(),
+        );
+        tokenizer.add_regex(
+            RegexRule::new(r"[a-z]+|(?<Quoted>`[a-z]+`)", "Identifier", Some("Plain"))
+                .with_group_subtypes(),
+        );
+        tokenizer.compile().expect("Compilation failed");
+
+        let result = tokenizer
+            .tokenize("0x1F 2.5e3 7 abc `def`")
+            .expect("Tokenization failed");
+        let tokens: Vec<(&str, Option<&str>)> = result
+            .iter()
+            .map(|t| (t.value.as_str(), t.token_sub_type.as_deref()))
+            .collect();
+        assert_eq!(
+            tokens,
+            vec![
+                ("0x1F", Some("Hex")),
+                ("2.5e3", Some("Float")),
+                ("7", Some("Integer")),
+                ("abc", Some("Plain")),
+                ("`def`", Some("Quoted")),
+            ]
+        );
+    }
+
+    #[test]
+    fn captures_are_attached_to_tokens() {
+        let mut tokenizer = Tokenizer::new();
+        tokenizer.add_regex(
+            RegexRule::new(r"\|(?<name>[a-zA-Z][a-zA-Z0-9_]*):|\|", "Pipe", None).with_captures(),
+        );
+        tokenizer.add_regex(RegexRule::new(r"(\d+)([a-z]+)?", "Number", None).with_captures());
+        tokenizer.compile().expect("Compilation failed");
+
+        let input = "10px |map: 3 |";
+        let result = tokenizer.tokenize(input).expect("Tokenization failed");
+
+        let number = &result[0];
+        assert_eq!(number.capture_at(1).unwrap().text, "10");
+        assert_eq!(number.capture_at(2).unwrap().text, "px");
+        assert!(number.capture("name").is_none());
+
+        let pipe = &result[1];
+        let name = pipe.capture("name").unwrap();
+        assert_eq!(name.text, "map");
+        assert_eq!(name.index, 1);
+        assert_eq!(name.span, Span::new(6, 9));
+        assert_eq!(name.span.text(input), "map");
+
+        // Groups that did not take part in the match are left out
+        assert!(result[2].capture_at(2).is_none());
+        assert!(result[3].capture("name").is_none());
+
+        let borrowed = tokenizer.borrowed_tokens(input).nth(1).unwrap().unwrap();
+        assert_eq!(borrowed.capture("name"), Some(name));
+    }
+}
@@ -0,0 +1,153 @@
+extern crate rb_tokenizer;
+
+use rb_tokenizer::rules::Match;
+use rb_tokenizer::tokens::Token;
+use rb_tokenizer::Tokenizer;
+
+// A JS-like dialect where `/` starts a regex literal unless it follows an
+// operand
+fn get_tokenizer() -> Tokenizer {
+    let mut tokenizer = Tokenizer::new();
+
+    tokenizer.add_closure_rule(Box::new(|input, context| {
+        if !input.starts_with('/') {
+            return Ok(None);
+        }
+        let after_operand = context
+            .previous_significant()
+            .is_some_and(|t| matches!(t.token_type.as_str(), "Number" | "Identifier"));
+        if after_operand {
+            return Ok(None);
+        }
+        match input[1..].find('/') {
+            Some(end) => Ok(Some(Token::new("Regex", None, &input[..end + 2]).into())),
+            None => Ok(None),
+        }
+    }));
+    tokenizer.add_regex_rule(r"^\d+", "Number", None);
+    tokenizer.add_regex_rule(r"^[a-zA-Z_][a-zA-Z0-9_]*", "Identifier", None);
+    tokenizer.add_symbol_rule("/", "Operator", Some("Divide"));
+    tokenizer.add_symbol_rule("=", "Operator", Some("Assign"));
+
+    tokenizer
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::get_tokenizer;
+    use rb_tokenizer::rules::RuleContext;
+    use rb_tokenizer::WhitespacePolicy;
+
+    fn types(tokenizer: &Tokenizer, input: &str) -> Vec<String> {
+        tokenizer
+            .tokenize(input)
+            .expect("Tokenization failed")
+            .into_iter()
+            .map(|t| t.token_type)
+            .collect()
+    }
+
+    #[test]
+    fn previous_significant_token_decides_slash() {
+        let tokenizer = get_tokenizer();
+
+        assert_eq!(
+            types(&tokenizer, "x = /ab/"),
+            vec!["Identifier", "Operator", "Regex"]
+        );
+        assert_eq!(
+            types(&tokenizer, "x = a / b / c"),
+            vec![
+                "Identifier",
+                "Operator",
+                "Identifier",
+                "Operator",
+                "Identifier",
+                "Operator",
+                "Identifier"
+            ]
+        );
+    }
+
+    #[test]
+    fn emitted_trivia_is_not_significant() {
+        let mut tokenizer = get_tokenizer();
+        tokenizer.set_whitespace_policy(WhitespacePolicy::Emit);
+
+        assert_eq!(
+            types(&tokenizer, "6 /2/ 3"),
+            vec![
+                "Number",
+                "Whitespace",
+                "Operator",
+                "Number",
+                "Operator",
+                "Whitespace",
+                "Number"
+            ]
+        );
+    }
+
+    #[test]
+    fn context_exposes_the_position() {
+        let mut tokenizer = Tokenizer::new();
+        tokenizer.add_closure_rule(Box::new(|input, context| {
+            let c = input.chars().next().unwrap();
+            let position = format!(
+                "{}{}:{}:{}:{}",
+                c,
+                context.offset(),
+                context.line(),
+                context.column(),
+                context.mode()
+            );
+            assert_eq!(&context.source()[context.offset()..], input);
+            Ok(Some(Match::new(
+                c.len_utf8(),
+                Token::new("Char", None, &position),
+            )))
+        }));
+
+        let result = tokenizer.tokenize("ab\né").expect("Tokenization failed");
+        let values: Vec<&str> = result.iter().map(|t| t.value.as_str()).collect();
+        assert_eq!(
+            values,
+            vec!["a0:1:1:default", "b1:1:2:default", "é3:2:1:default"]
+        );
+    }
+
+    #[test]
+    fn rules_can_mutate_the_state() {
+        let mut tokenizer = Tokenizer::new();
+        tokenizer.add_closure_rule(Box::new(|input, context| {
+            let depth = match input.chars().next() {
+                Some('(') => {
+                    *context.state::<usize>().unwrap() += 1;
+                    *context.state::<usize>().unwrap()
+                }
+                Some(')') => {
+                    let depth = context.state::<usize>().unwrap();
+                    *depth -= 1;
+                    *depth + 1
+                }
+                _ => return Ok(None),
+            };
+            let token = Token::new("Paren", Some(&depth.to_string()), &input[..1]);
+            Ok(Some(token.into()))
+        }));
+
+        let mut depth = 0usize;
+        let sub_types: Vec<String> = tokenizer
+            .tokens("(()())")
+            .with_state(&mut depth)
+            .map(|t| t.unwrap().token_sub_type.unwrap())
+            .collect();
+        assert_eq!(sub_types, vec!["1", "2", "2", "2", "2", "1"]);
+        assert_eq!(depth, 0);
+
+        // Without state, or with state of another type, there is none
+        let mut context = RuleContext::<String>::new("");
+        assert!(context.state::<usize>().is_none());
+    }
+}
@@ -0,0 +1,127 @@
+extern crate rb_tokenizer;
+
+use rb_tokenizer::rules::{CallbackRule, Match, RuleContext};
+use rb_tokenizer::tokens::{Token, TokenizationError};
+use rb_tokenizer::Tokenizer;
+
+// Emits a synthetic `Semicolon` token for a line break, like automatic
+// semicolon insertion
+struct LineBreakRule;
+
+impl CallbackRule for LineBreakRule {
+    fn process(
+        &self,
+        input: &str,
+        _context: &mut RuleContext,
+    ) -> Result<Option<Match<Token>>, TokenizationError> {
+        if input.starts_with('\n') {
+            Ok(Some(Match::new(1, Token::new("Semicolon", None, ";"))))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+fn get_tokenizer() -> Tokenizer {
+    let mut tokenizer = Tokenizer::new();
+    tokenizer.set_whitespace_chars(&[' ']);
+
+    tokenizer.add_regex_rule(r"^\d+", "Number", None);
+    tokenizer.add_symbol_rule("+", "Operator", Some("Plus"));
+    tokenizer.add_callback_rule(Box::new(LineBreakRule));
+
+    // Comments are consumed without emitting a token
+    tokenizer.add_closure_rule(Box::new(|input, _| {
+        if input.starts_with("//") {
+            Ok(Some(Match::skip(input.find('\n').unwrap_or(input.len()))))
+        } else {
+            Ok(None)
+        }
+    }));
+
+    // Character literals are emitted with their value decoded
+    tokenizer.add_closure_rule(Box::new(|input, _| {
+        let value = match input.get(..4) {
+            Some(r"'\n'") => "\n",
+            Some(r"'\t'") => "\t",
+            _ => return Ok(None),
+        };
+        Ok(Some(Match::new(4, Token::new("Char", None, value))))
+    }));
+
+    tokenizer
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::get_tokenizer;
+    use rb_tokenizer::rules::Match;
+    use rb_tokenizer::tokens::{Span, Token, TokenizationError};
+
+    #[test]
+    fn skipped_input_emits_nothing() {
+        let tokenizer = get_tokenizer();
+        let result = tokenizer
+            .tokenize("1 + // one\n2 // two")
+            .expect("Tokenization failed");
+
+        let values: Vec<&str> = result.iter().map(|t| t.value.as_str()).collect();
+        assert_eq!(values, vec!["1", "+", ";", "2"]);
+    }
+
+    #[test]
+    fn synthetic_tokens_span_the_consumed_input() {
+        let tokenizer = get_tokenizer();
+        let input = "1\n2";
+        let result = tokenizer.tokenize(input).expect("Tokenization failed");
+
+        assert_eq!(result[1].value, ";");
+        assert_eq!(result[1].span, Span::new(1, 2));
+        assert_eq!(result[1].text(input), "\n");
+        assert_eq!((result[2].line, result[2].column), (2, 1));
+    }
+
+    #[test]
+    fn decoded_values_do_not_change_the_consumed_length() {
+        let tokenizer = get_tokenizer();
+        let input = r"'\n' + '\t'";
+        let result = tokenizer.tokenize(input).expect("Tokenization failed");
+
+        let values: Vec<&str> = result.iter().map(|t| t.value.as_str()).collect();
+        assert_eq!(values, vec!["\n", "+", "\t"]);
+        assert_eq!(result[2].span, Span::new(7, 11));
+        assert_eq!(result[2].column, 8);
+    }
+
+    #[test]
+    fn zero_length_matches_are_rejected() {
+        let mut tokenizer = get_tokenizer();
+        tokenizer.add_closure_rule(Box::new(|_, _| Ok(Some(Match::skip(0)))));
+
+        let errors: Vec<_> = tokenizer.tokens("1 @").filter_map(Result::err).collect();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].span(), Span::new(2, 2));
+    }
+
+    #[test]
+    fn matches_can_report_errors_they_recovered_from() {
+        let mut tokenizer = get_tokenizer();
+        // Unknown escapes are kept as written and reported
+        tokenizer.add_closure_rule(Box::new(|input, _| {
+            if !input.starts_with(r"'\") || input.get(3..4) != Some("'") {
+                return Ok(None);
+            }
+            let error = TokenizationError::invalid_escape(&input[1..3], Span::new(1, 3));
+            Ok(Some(
+                Match::new(4, Token::new("Char", None, &input[1..3])).with_error(error),
+            ))
+        }));
+
+        let (tokens, errors) = tokenizer.tokenize_with_diagnostics(r"1 + '\q'");
+        let values: Vec<&str> = tokens.iter().map(|t| t.value.as_str()).collect();
+        assert_eq!(values, vec!["1", "+", r"\q"]);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].span(), Span::new(5, 7));
+        assert_eq!(errors[0].column(), 6);
+    }
+}
@@ -0,0 +1,56 @@
+extern crate rb_tokenizer;
+
+use rb_tokenizer::Tokenizer;
+
+fn get_tokenizer() -> Tokenizer {
+    let mut tokenizer = Tokenizer::new();
+
+    tokenizer.add_regex_rule(r"^\d+", "Number", None);
+    tokenizer.add_regex_rule(r"^[a-zA-Z_][a-zA-Z0-9_]*", "Identifier", None);
+    tokenizer.add_symbol_rule("(", "Operator", Some("OpenParen"));
+    tokenizer.add_symbol_rule(")", "Operator", Some("CloseParen"));
+    tokenizer.add_symbol_rule("+", "Operator", Some("Plus"));
+
+    tokenizer
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::get_tokenizer;
+    use rb_tokenizer::tokens::Span;
+
+    #[test]
+    fn tokens_carry_byte_spans() {
+        let tokenizer = get_tokenizer();
+        let input = "ADD(2 +\n  40)";
+        let result = tokenizer.tokenize(input).expect("Tokenization failed");
+
+        let spans: Vec<Span> = result.iter().map(|t| t.span).collect();
+        assert_eq!(
+            spans,
+            vec![
+                Span::new(0, 3),
+                Span::new(3, 4),
+                Span::new(4, 5),
+                Span::new(6, 7),
+                Span::new(10, 12),
+                Span::new(12, 13),
+            ]
+        );
+        for token in &result {
+            assert_eq!(token.text(input), token.value);
+        }
+    }
+
+    #[test]
+    fn spans_merge_into_source_ranges() {
+        let tokenizer = get_tokenizer();
+        let input = "ADD(2 + 40)";
+        let result = tokenizer.tokenize(input).expect("Tokenization failed");
+
+        let call = result[0].span.merge(result[result.len() - 1].span);
+        assert_eq!(call.text(input), input);
+        assert_eq!(result[2].span.merge(result[4].span).text(input), "2 + 40");
+        assert_eq!(call.len(), input.len());
+    }
+}
@@ -0,0 +1,87 @@
+extern crate rb_tokenizer;
+
+use rb_tokenizer::rules::Match;
+use rb_tokenizer::tokens::Token;
+use rb_tokenizer::Tokenizer;
+
+// Tracks the nesting depth of brackets and reports unbalanced closings
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Nesting {
+    depth: usize,
+    max_depth: usize,
+}
+
+fn get_tokenizer() -> Tokenizer {
+    let mut tokenizer = Tokenizer::new();
+
+    tokenizer.add_regex_rule(r"^\d+", "Number", None);
+    tokenizer.add_closure_rule(Box::new(|input, context| {
+        let nesting = context.state::<Nesting>().expect("missing state");
+        let sub_type = if input.starts_with('[') {
+            nesting.depth += 1;
+            nesting.max_depth = nesting.max_depth.max(nesting.depth);
+            "Open"
+        } else if input.starts_with(']') && nesting.depth > 0 {
+            nesting.depth -= 1;
+            "Close"
+        } else {
+            return Ok(None);
+        };
+        Ok(Some(Match::new(
+            1,
+            Token::new("Bracket", Some(sub_type), &input[..1]),
+        )))
+    }));
+
+    tokenizer
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{get_tokenizer, Nesting};
+
+    #[test]
+    fn state_is_handed_back_to_the_caller() {
+        let tokenizer = get_tokenizer();
+
+        let mut nesting = Nesting::default();
+        let result = tokenizer
+            .tokenize_with_state("[1 [2 [3]] []]", &mut nesting)
+            .expect("Tokenization failed");
+        assert_eq!(result.len(), 11);
+        assert_eq!(
+            nesting,
+            Nesting {
+                depth: 0,
+                max_depth: 3
+            }
+        );
+
+        // An unbalanced `]` is not recognized
+        let mut nesting = Nesting::default();
+        assert!(tokenizer.tokenize_with_state("[1]]", &mut nesting).is_err());
+    }
+
+    #[test]
+    fn tokenizer_state_is_reset_for_every_run() {
+        let mut tokenizer = get_tokenizer();
+        tokenizer.set_state(Nesting::default());
+
+        // Each run starts from depth 0, so the second `]` is rejected every time
+        for _ in 0..3 {
+            let (tokens, errors) = tokenizer.tokenize_with_diagnostics("[[]] ]");
+            assert_eq!(tokens.len(), 4);
+            assert_eq!(errors.len(), 1);
+        }
+
+        // Explicit state takes precedence over the tokenizer's
+        let mut nesting = Nesting {
+            depth: 1,
+            max_depth: 1,
+        };
+        assert!(tokenizer
+            .tokenize_with_state("[[]] ]", &mut nesting)
+            .is_ok());
+        assert_eq!(nesting.max_depth, 3);
+    }
+}
@@ -0,0 +1,188 @@
+extern crate rb_tokenizer;
+
+use rb_tokenizer::rules::StringRule;
+use rb_tokenizer::Tokenizer;
+
+fn get_tokenizer() -> Tokenizer {
+    let mut tokenizer = Tokenizer::new();
+
+    tokenizer.add_rule(Box::new(
+        StringRule::new("String", None).quotes(&['"', '\'']),
+    ));
+    tokenizer.add_rule(Box::new(
+        StringRule::new("String", Some("Template"))
+            .quotes(&['`'])
+            .multiline(),
+    ));
+    tokenizer.add_regex_rule(r"[a-zA-Z_]\w*", "Identifier", None);
+    tokenizer.add_symbol_rule("+", "Operator", Some("Plus"));
+
+    tokenizer
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::get_tokenizer;
+    use rb_tokenizer::tokens::{Span, TokenizationError};
+    use std::borrow::Cow;
+
+    fn value(tokenizer: &Tokenizer, input: &str) -> String {
+        let tokens = tokenizer.tokenize(input).expect("Tokenization failed");
+        assert_eq!(tokens.len(), 1, "{:?}", tokens);
+        tokens[0].value.clone()
+    }
+
+    #[test]
+    fn escapes_are_decoded() {
+        let tokenizer = get_tokenizer();
+
+        assert_eq!(value(&tokenizer, r#""a\tb\nc""#), "a\tb\nc");
+        assert_eq!(
+            value(&tokenizer, r#""\"quoted\" \\ \'""#),
+            r#""quoted" \ '"#
+        );
+        assert_eq!(value(&tokenizer, r#""\x41\u00e9\u{1F600}""#), "Aé😀");
+        // UTF-16 surrogate pairs, as in JSON
+        assert_eq!(value(&tokenizer, r#""\uD83D\uDE00""#), "😀");
+        assert_eq!(value(&tokenizer, r#"'say "hi"'"#), r#"say "hi""#);
+        assert_eq!(value(&tokenizer, r#""""#), "");
+    }
+
+    #[test]
+    fn raw_text_is_kept_in_the_span() {
+        let tokenizer = get_tokenizer();
+        let input = r#"a + "x\ty" + b"#;
+
+        let tokens = tokenizer.tokenize(input).unwrap();
+        assert_eq!(tokens[2].value, "x\ty");
+        assert_eq!(tokens[2].span, Span::new(4, 10));
+        assert_eq!(tokens[2].text(input), r#""x\ty""#);
+        assert_eq!(tokens[4].value, "b");
+    }
+
+    #[test]
+    fn strings_without_escapes_are_borrowed() {
+        let tokenizer = get_tokenizer();
+
+        let tokens: Vec<_> = tokenizer
+            .borrowed_tokens(r#""plain" "esc\n""#)
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert!(matches!(tokens[0].value, Cow::Borrowed("plain")));
+        assert!(matches!(&tokens[1].value, Cow::Owned(value) if value == "esc\n"));
+    }
+
+    #[test]
+    fn invalid_escapes_are_reported_and_kept() {
+        let tokenizer = get_tokenizer();
+        let input = r#"x + "a\qb\é\u{110000}\uD800\x4""#;
+
+        let (tokens, errors) = tokenizer.tokenize_with_diagnostics(input);
+        assert_eq!(tokens[2].value, r"a\qb\é\u{110000}\uD800\x4");
+        let sequences: Vec<(&str, Span)> = errors
+            .iter()
+            .map(|e| match e {
+                TokenizationError::InvalidEscape { sequence, .. } => (sequence.as_str(), e.span()),
+                other => panic!("Unexpected error: {}", other),
+            })
+            .collect();
+        assert_eq!(
+            sequences,
+            vec![
+                (r"\q", Span::new(6, 8)),
+                (r"\é", Span::new(9, 12)),
+                (r"\u{110000}", Span::new(12, 22)),
+                (r"\uD800", Span::new(22, 28)),
+                (r"\x4", Span::new(28, 31)),
+            ]
+        );
+        assert_eq!(errors[0].column(), 7);
+    }
+
+    #[test]
+    fn unterminated_strings_end_at_the_line_break() {
+        let tokenizer = get_tokenizer();
+        let input = "a + \"open\r\nb";
+
+        let (tokens, errors) = tokenizer.tokenize_with_diagnostics(input);
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(
+            &errors[0],
+            TokenizationError::Unterminated { construct, .. } if construct == "string"
+        ));
+        assert_eq!(errors[0].span(), Span::new(4, 5));
+        assert_eq!((errors[0].line(), errors[0].column()), (1, 5));
+
+        // Tokenization goes on with the next line
+        assert_eq!(tokens[2].value, "open");
+        assert_eq!(tokens[3].value, "b");
+        assert_eq!(tokens[3].line, 2);
+    }
+
+    #[test]
+    fn escaped_line_breaks_end_single_line_strings() {
+        let tokenizer = get_tokenizer();
+        let input = "\"ab\\\nx y";
+
+        // The escape character ends the string with the line, and is not an escape error
+        let (tokens, errors) = tokenizer.tokenize_with_diagnostics(input);
+        let values: Vec<&str> = tokens.iter().map(|t| t.value.as_str()).collect();
+        assert_eq!(values, vec!["ab", "x", "y"]);
+        assert_eq!(tokens[0].span, Span::new(0, 4));
+
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(errors[0], TokenizationError::Unterminated { .. }));
+    }
+
+    #[test]
+    fn trailing_escapes_end_strings() {
+        let tokenizer = get_tokenizer();
+
+        for input in ["\"ab\\", "`ab\\"] {
+            let (tokens, errors) = tokenizer.tokenize_with_diagnostics(input);
+            assert_eq!(tokens.len(), 1);
+            assert_eq!(tokens[0].value, "ab");
+            assert_eq!(tokens[0].span, Span::new(0, 4));
+
+            assert_eq!(errors.len(), 1);
+            assert!(matches!(errors[0], TokenizationError::Unterminated { .. }));
+        }
+    }
+
+    #[test]
+    fn line_continuations_in_multiline_strings() {
+        let tokenizer = get_tokenizer();
+
+        assert_eq!(value(&tokenizer, "`one \\\ntwo`"), "one two");
+        assert_eq!(value(&tokenizer, "`one \\\r\ntwo`"), "one two");
+    }
+
+    #[test]
+    fn multiline_strings() {
+        let tokenizer = get_tokenizer();
+
+        assert_eq!(value(&tokenizer, "`one\ntwo\\t`"), "one\ntwo\t");
+
+        let (tokens, errors) = tokenizer.tokenize_with_diagnostics("`one\ntwo");
+        assert_eq!(tokens[0].value, "one\ntwo");
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].span(), Span::new(0, 1));
+    }
+
+    #[test]
+    fn raw_strings_and_custom_escapes() {
+        let mut tokenizer = Tokenizer::new();
+        tokenizer.add_rule(Box::new(StringRule::new("Raw", None).quotes(&['|']).raw()));
+        tokenizer.add_rule(Box::new(
+            StringRule::new("String", None)
+                .escape_char('^')
+                .with_escape('b', '\u{8}')
+                .with_escape('n', '¶'),
+        ));
+
+        let tokens = tokenizer.tokenize(r#"|C:\temp\n| "^"^b^n^^\n""#).unwrap();
+        assert_eq!(tokens[0].value, r"C:\temp\n");
+        assert_eq!(tokens[1].value, "\"\u{8}¶^\\n");
+    }
+}
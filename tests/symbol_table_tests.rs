@@ -0,0 +1,158 @@
+extern crate rb_tokenizer;
+
+use rb_tokenizer::Tokenizer;
+
+fn get_tokenizer() -> Tokenizer {
+    let mut tokenizer = Tokenizer::new();
+
+    tokenizer.add_regex_rule(r"\d+", "Number", None);
+    tokenizer.add_regex_rule(r"[a-zA-Z_]\w*", "Identifier", None);
+    // Shorter symbols first on purpose, the table does not care
+    tokenizer.add_symbols(&[
+        ("<", "Operator", Some("Less")),
+        ("<=", "Operator", Some("LessEqual")),
+        ("<<", "Operator", Some("ShiftLeft")),
+        ("<<=", "Operator", Some("ShiftLeftAssign")),
+        ("=", "Operator", Some("Assign")),
+        ("==", "Operator", Some("Equal")),
+        ("=>", "Arrow", None),
+        ("→", "Arrow", Some("Unicode")),
+        ("(", "Punctuation", Some("OpenParen")),
+        (")", "Punctuation", Some("CloseParen")),
+    ]);
+
+    tokenizer
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::get_tokenizer;
+    use rb_tokenizer::rules::{FirstBytes, RegexRule, Rule, SymbolTableRule};
+    use rb_tokenizer::Tokenizer;
+
+    fn sub_types(tokenizer: &Tokenizer, input: &str) -> Vec<String> {
+        tokenizer
+            .tokenize(input)
+            .expect("Tokenization failed")
+            .into_iter()
+            .map(|t| t.token_sub_type.unwrap_or(t.token_type))
+            .collect()
+    }
+
+    #[test]
+    fn longest_symbol_wins_regardless_of_order() {
+        let tokenizer = get_tokenizer();
+
+        assert_eq!(
+            sub_types(&tokenizer, "a <<= 1 == b <= c < (d) => e → f"),
+            vec![
+                "Identifier",
+                "ShiftLeftAssign",
+                "Number",
+                "Equal",
+                "Identifier",
+                "LessEqual",
+                "Identifier",
+                "Less",
+                "OpenParen",
+                "Identifier",
+                "CloseParen",
+                "Arrow",
+                "Identifier",
+                "Unicode",
+                "Identifier",
+            ]
+        );
+    }
+
+    #[test]
+    fn compiled_tokenizer_gives_the_same_tokens() {
+        let tokenizer = get_tokenizer();
+        let mut compiled = tokenizer.clone();
+        compiled.compile().expect("Compilation failed");
+
+        let input = "x<<=y<=(z==1)=>w<<2";
+        assert_eq!(
+            compiled.tokenize(input).unwrap(),
+            tokenizer.tokenize(input).unwrap()
+        );
+    }
+
+    #[test]
+    fn compiled_rules_sharing_a_first_byte_give_the_same_tokens() {
+        // Enough rules start with `<` for the compiled automaton to pick the
+        // winner, including a table whose longest symbol is added last
+        let mut tokenizer = Tokenizer::new();
+        tokenizer.add_symbol_rule("<!--", "Comment", None);
+        tokenizer.add_regex(RegexRule::new(r"<(?<tag>[a-z]+)>", "Tag", None).with_captures());
+        tokenizer.add_regex_rule(r"<\d", "Digit", None);
+        tokenizer.add_symbols(&[
+            ("<", "Operator", Some("Less")),
+            ("<=", "Operator", Some("LessEqual")),
+            ("<<", "Operator", Some("ShiftLeft")),
+            ("<<=", "Operator", Some("ShiftLeftAssign")),
+        ]);
+        tokenizer.add_symbol_rule("<-", "Arrow", None);
+        tokenizer.add_regex_rule(r"[a-z]+", "Identifier", None);
+        let mut compiled = tokenizer.clone();
+        compiled.compile().expect("Compilation failed");
+
+        let input = "<b> <!-- <1 <a <<= <=<";
+        let expected = tokenizer.tokenize(input).unwrap();
+        let result = compiled.tokenize(input).unwrap();
+        assert_eq!(result, expected);
+        assert_eq!(result[0].capture("tag").unwrap().text, "b");
+    }
+
+    #[test]
+    fn borrowed_tokens_use_the_table() {
+        let tokenizer = get_tokenizer();
+
+        let tokens: Vec<_> = tokenizer
+            .borrowed_tokens("<<=<")
+            .collect::<Result<_, _>>()
+            .unwrap();
+        let values: Vec<_> = tokens.iter().map(|t| t.value.as_ref()).collect();
+        assert_eq!(values, vec!["<<=", "<"]);
+    }
+
+    #[test]
+    fn mode_actions_apply_to_the_whole_table() {
+        let mut tokenizer = Tokenizer::new();
+        tokenizer
+            .add_symbols(&[("{", "Open", None), ("{{", "Open", Some("Template"))])
+            .push_mode("inner");
+        tokenizer.with_mode("inner", |t| {
+            t.add_symbols(&[("}", "Close", None), ("}}", "Close", Some("Template"))])
+                .pop_mode();
+            t.add_regex_rule(r"[^{}]+", "Text", None);
+        });
+        tokenizer.add_regex_rule(r"\s+", "Space", None);
+
+        assert_eq!(
+            sub_types(&tokenizer, "{{ a }}{b}"),
+            vec!["Template", "Text", "Template", "Open", "Text", "Close"]
+        );
+    }
+
+    #[test]
+    fn table_without_ordering_hazards() {
+        let tokenizer = get_tokenizer();
+
+        assert_eq!(tokenizer.analyze(), vec![]);
+    }
+
+    #[test]
+    fn later_symbols_replace_earlier_ones() {
+        let table = SymbolTableRule::<String>::new()
+            .with("+", "Operator", Some("Plus"))
+            .with("", "Nothing", None)
+            .with("+", "Operator", Some("Add"));
+
+        assert_eq!(table.symbols().len(), 1);
+        let rule = table.longest_match("+1").unwrap();
+        assert_eq!(rule.token_sub_type.as_deref(), Some("Add"));
+        assert!(table.longest_match("-1").is_none());
+        assert_eq!(table.first_bytes(), FirstBytes::from_iter([b'+']));
+    }
+}
@@ -0,0 +1,84 @@
+extern crate rb_tokenizer;
+
+use std::sync::{Arc, LazyLock};
+use std::thread;
+
+use rb_tokenizer::rules::Match;
+use rb_tokenizer::tokens::Token;
+use rb_tokenizer::Tokenizer;
+
+fn get_tokenizer() -> Tokenizer {
+    let mut tokenizer = Tokenizer::new();
+
+    // Captured data must be shareable between threads as well
+    let constants = Arc::new(vec!["pi", "tau"]);
+    tokenizer.add_closure_rule(Box::new(move |input, _| {
+        let found = constants.iter().find(|c| input.starts_with(**c));
+        Ok(found.map(|c| Match::new(c.len(), Token::new("Constant", None, c))))
+    }));
+
+    tokenizer.add_regex_rule(r"^\d+", "Number", None);
+    tokenizer.add_regex_rule(r"^[a-zA-Z_][a-zA-Z0-9_]*", "Identifier", None);
+    tokenizer.add_symbol_rule("+", "Operator", Some("Plus"));
+
+    tokenizer
+}
+
+static TOKENIZER: LazyLock<Tokenizer> = LazyLock::new(get_tokenizer);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    fn assert_send<T: Send>(_: &T) {}
+
+    #[test]
+    fn tokenizer_is_send_and_sync() {
+        assert_send_sync::<Tokenizer>();
+    }
+
+    #[test]
+    fn token_streams_are_send() {
+        let mut tokenizer = get_tokenizer();
+        assert_send(&tokenizer.tokens("pi + 1"));
+        assert_send(&tokenizer.borrowed_tokens("pi + 1"));
+
+        tokenizer.set_state(0usize);
+        let mut depth = 0usize;
+        let stream = tokenizer.tokens("pi + 1").with_state(&mut depth);
+        assert_send(&stream);
+        assert_eq!(thread::scope(|s| s.spawn(|| stream.count()).join().unwrap()), 3);
+    }
+
+    #[test]
+    fn static_tokenizer_is_shared_between_threads() {
+        let handles: Vec<_> = (0..4)
+            .map(|i| {
+                thread::spawn(move || {
+                    let input = format!("pi + {}", i);
+                    TOKENIZER.tokenize(&input).expect("Tokenization failed")
+                })
+            })
+            .collect();
+
+        for (i, handle) in handles.into_iter().enumerate() {
+            let tokens = handle.join().unwrap();
+            let values: Vec<&str> = tokens.iter().map(|t| t.value.as_str()).collect();
+            assert_eq!(values, vec!["pi", "+", &i.to_string()]);
+            assert_eq!(tokens[0].token_type, "Constant");
+        }
+    }
+
+    #[test]
+    fn clones_are_independent() {
+        let tokenizer = get_tokenizer();
+        let mut extended = tokenizer.clone();
+        extended.add_symbol_rule("-", "Operator", Some("Minus"));
+
+        assert!(extended.tokenize("tau - 1").is_ok());
+        assert!(tokenizer.tokenize("tau - 1").is_err());
+        assert!(tokenizer.tokenize("tau + 1").is_ok());
+    }
+}
@@ -0,0 +1,96 @@
+extern crate rb_tokenizer;
+
+use rb_tokenizer::tokens::{Token, TokenKind};
+use rb_tokenizer::{RecoveryPolicy, Tokenizer};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Kind {
+    Number,
+    Identifier,
+    Operator,
+    Whitespace,
+    Newline,
+    Error,
+}
+
+impl TokenKind for Kind {
+    fn error() -> Self {
+        Kind::Error
+    }
+
+    fn whitespace() -> Self {
+        Kind::Whitespace
+    }
+
+    fn newline() -> Self {
+        Kind::Newline
+    }
+}
+
+fn get_tokenizer() -> Tokenizer<Kind> {
+    let mut tokenizer = Tokenizer::default();
+    tokenizer.set_recovery_policy(RecoveryPolicy::SkipChar);
+
+    tokenizer.add_regex_rule(r"^\d+", Kind::Number, None);
+    tokenizer.add_regex_rule(r"^[a-zA-Z_][a-zA-Z0-9_]*", Kind::Identifier, None);
+    tokenizer.add_symbol_rule("+", Kind::Operator, Some("Plus"));
+    tokenizer.add_closure_rule(Box::new(|input, _| {
+        if input.starts_with('π') {
+            Ok(Some(
+                Token::with_kind(Kind::Number, Some("Constant"), "π").into(),
+            ))
+        } else {
+            Ok(None)
+        }
+    }));
+
+    tokenizer
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{get_tokenizer, Kind};
+
+    #[test]
+    fn tokens_use_the_user_kind() {
+        let tokenizer = get_tokenizer();
+        let result = tokenizer
+            .tokenize("x + 42 + π")
+            .expect("Tokenization failed");
+
+        let kinds: Vec<Kind> = result.iter().map(|t| t.token_type).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                Kind::Identifier,
+                Kind::Operator,
+                Kind::Number,
+                Kind::Operator,
+                Kind::Number
+            ]
+        );
+
+        // Matching on the kind is exhaustive
+        let numbers = result
+            .iter()
+            .filter(|t| match t.token_type {
+                Kind::Number => true,
+                Kind::Identifier
+                | Kind::Operator
+                | Kind::Whitespace
+                | Kind::Newline
+                | Kind::Error => false,
+            })
+            .count();
+        assert_eq!(numbers, 2);
+    }
+
+    #[test]
+    fn recovered_input_uses_the_error_kind() {
+        let tokenizer = get_tokenizer();
+        let (tokens, errors) = tokenizer.tokenize_with_diagnostics("1 @ 2");
+
+        assert_eq!(tokens[1].token_type, Kind::Error);
+        assert_eq!(errors.len(), 1);
+    }
+}
@@ -0,0 +1,48 @@
+extern crate rb_tokenizer;
+
+use rb_tokenizer::Tokenizer;
+
+fn get_tokenizer() -> Tokenizer {
+    let mut tokenizer = Tokenizer::new();
+
+    tokenizer.add_regex_rule(r"^\d+", "Number", None);
+    tokenizer.add_regex_rule(r"^[a-zA-Z_][a-zA-Z0-9_]*", "Identifier", None);
+    tokenizer.add_symbol_rule("+", "Operator", Some("Plus"));
+
+    tokenizer
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::get_tokenizer;
+
+    #[test]
+    fn stream_matches_tokenize() {
+        let tokenizer = get_tokenizer();
+        let input = "a + 12\n+ b";
+
+        let streamed: Vec<_> = tokenizer
+            .tokens(input)
+            .collect::<Result<_, _>>()
+            .expect("Tokenization failed");
+        let collected = tokenizer.tokenize(input).expect("Tokenization failed");
+
+        assert_eq!(streamed, collected);
+        assert_eq!((streamed[3].line, streamed[3].column), (2, 1));
+    }
+
+    #[test]
+    fn stream_stops_lazily_before_errors() {
+        let tokenizer = get_tokenizer();
+        let mut stream = tokenizer.tokens("1 + 2 @ 3");
+
+        let first = stream.next().unwrap().expect("Tokenization failed");
+        assert_eq!(first.value, "1");
+        assert_eq!((stream.line(), stream.column()), (1, 2));
+
+        // Pulling past the stray `@` yields the error and then ends the stream
+        let rest: Vec<_> = stream.collect();
+        assert_eq!(rest.len(), 3);
+        assert!(rest[2].is_err());
+    }
+}
@@ -71,6 +71,7 @@ fn get_tokenizer() -> Tokenizer {
 #[cfg(test)]
 mod tests {
     use crate::get_tokenizer;
+    use rb_tokenizer::MatchStrategy;
 
     #[test]
     fn it_works() {
@@ -78,4 +79,36 @@ mod tests {
         let result = tokenizer.tokenize(r"[1, 2, 3, 4] |map: RAND() * $1 |filter: $1 % 2 == 0");
         println!("{:?}", result);
     }
+
+    #[test]
+    fn longest_match_lexes_compound_operators() {
+        let mut tokenizer = get_tokenizer();
+        tokenizer.set_match_strategy(MatchStrategy::LongestMatch);
+        let result = tokenizer
+            .tokenize(r"$1 <= 2 && $2 >> 1 != 0")
+            .expect("Tokenization failed");
+
+        let values: Vec<&str> = result.iter().map(|t| t.value.as_str()).collect();
+        assert_eq!(
+            values,
+            vec!["$1", "<=", "2", "&&", "$2", ">>", "1", "!=", "0"]
+        );
+    }
+
+    #[test]
+    fn compiled_tokenizer_matches_uncompiled() {
+        let input = r"[1, 2, 3, 4] |map: RAND() * $1 |filter: $1 % 2 == 0 && `raw` != 'x'";
+        let tokenizer = get_tokenizer();
+        let expected = tokenizer.tokenize(input).expect("Tokenization failed");
+
+        let mut compiled = get_tokenizer();
+        compiled.compile().expect("Compilation failed");
+        assert!(compiled.is_compiled());
+        assert_eq!(compiled.tokenize(input).unwrap(), expected);
+
+        // Adding a rule drops the compiled form until the next compile
+        compiled.add_symbol_rule("?", "Operator", Some("Question"));
+        assert!(!compiled.is_compiled());
+        assert_eq!(compiled.tokenize(input).unwrap(), expected);
+    }
 }
@@ -0,0 +1,105 @@
+extern crate rb_tokenizer;
+
+use rb_tokenizer::rules::Match;
+use rb_tokenizer::tokens::{Span, Token};
+use rb_tokenizer::Tokenizer;
+
+fn get_tokenizer() -> Tokenizer {
+    let mut tokenizer = Tokenizer::new();
+
+    tokenizer.add_regex_rule(r"^\d+", "Number", None);
+    tokenizer.add_regex_rule(r"^\p{L}[\p{L}\p{M}\d_]*", "Identifier", None);
+    tokenizer.add_symbol_rule("+", "Operator", Some("Plus"));
+    tokenizer.add_symbol_rule("=", "Operator", Some("Assign"));
+    tokenizer.add_symbol_rule("→", "Arrow", None);
+
+    tokenizer
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::get_tokenizer;
+    use rb_tokenizer::ColumnUnit;
+
+    fn positions(tokenizer: &Tokenizer, input: &str) -> Vec<(String, usize, usize)> {
+        tokenizer
+            .tokenize(input)
+            .expect("Tokenization failed")
+            .into_iter()
+            .map(|t| (t.value, t.line, t.column))
+            .collect()
+    }
+
+    #[test]
+    fn multi_byte_tokens_do_not_swallow_input() {
+        let tokenizer = get_tokenizer();
+        let input = "café+1 → 名前 = नमस्ते";
+        let result = tokenizer.tokenize(input).expect("Tokenization failed");
+
+        let values: Vec<&str> = result.iter().map(|t| t.value.as_str()).collect();
+        assert_eq!(values, vec!["café", "+", "1", "→", "名前", "=", "नमस्ते"]);
+        for token in &result {
+            assert_eq!(token.text(input), token.value);
+        }
+        assert_eq!(result[1].span, Span::new(5, 6));
+    }
+
+    #[test]
+    fn columns_count_chars_by_default() {
+        let tokenizer = get_tokenizer();
+        let columns: Vec<usize> = positions(&tokenizer, "名前 = 1\nx")
+            .into_iter()
+            .map(|(_, _, column)| column)
+            .collect();
+        assert_eq!(columns, vec![1, 4, 6, 1]);
+    }
+
+    #[test]
+    fn column_units() {
+        let mut tokenizer = get_tokenizer();
+        tokenizer.add_symbol_rule("😀", "Emoji", None);
+        let input = "😀 é\nनमस्ते x";
+
+        let expected = [
+            (ColumnUnit::Chars, [1, 3, 1, 8]),
+            (ColumnUnit::Utf16, [1, 4, 1, 8]),
+            (ColumnUnit::Graphemes, [1, 3, 1, 5]),
+            (ColumnUnit::Bytes, [1, 6, 1, 20]),
+        ];
+        for (unit, columns) in expected {
+            tokenizer.set_column_unit(unit);
+            let result: Vec<usize> = positions(&tokenizer, input)
+                .into_iter()
+                .map(|(_, _, column)| column)
+                .collect();
+            assert_eq!(result, columns, "{:?}", unit);
+        }
+    }
+
+    #[test]
+    fn rules_consume_independently_of_value() {
+        let mut tokenizer = get_tokenizer();
+        // Numbers with `_` separators, emitted without them
+        tokenizer.add_closure_rule(Box::new(|input, _| {
+            let len = input
+                .find(|c: char| !c.is_ascii_digit() && c != '_')
+                .unwrap_or(input.len());
+            if len == 0 {
+                return Ok(None);
+            }
+            Ok(Some(Match::new(
+                len,
+                Token::new("Number", None, &input[..len].replace('_', "")),
+            )))
+        }));
+        tokenizer.set_match_strategy(rb_tokenizer::MatchStrategy::LongestMatch);
+
+        let input = "1_000_000 + é";
+        let result = tokenizer.tokenize(input).expect("Tokenization failed");
+        assert_eq!(result[0].value, "1000000");
+        assert_eq!(result[0].span, Span::new(0, 9));
+        assert_eq!(result[1].column, 11);
+        assert_eq!(result[2].value, "é");
+    }
+}
@@ -0,0 +1,123 @@
+extern crate rb_tokenizer;
+
+use rb_tokenizer::{Tokenizer, WhitespacePolicy};
+
+fn get_tokenizer(policy: WhitespacePolicy) -> Tokenizer {
+    let mut tokenizer = Tokenizer::new();
+    tokenizer.set_whitespace_policy(policy);
+
+    tokenizer.add_regex_rule(r"^\d+", "Number", None);
+    tokenizer.add_regex_rule(r"^[a-zA-Z_][a-zA-Z0-9_]*", "Identifier", None);
+    tokenizer.add_symbol_rule("=", "Operator", Some("Assign"));
+
+    tokenizer
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::get_tokenizer;
+    use rb_tokenizer::tokenizers::{NEWLINE_TOKEN_TYPE, WHITESPACE_TOKEN_TYPE};
+    use rb_tokenizer::tokens::Span;
+    use rb_tokenizer::{RecoveryPolicy, WhitespacePolicy};
+
+    #[test]
+    fn skip_drops_whitespace() {
+        let tokenizer = get_tokenizer(WhitespacePolicy::Skip);
+        let result = tokenizer.tokenize("x = 1\n").expect("Tokenization failed");
+
+        let values: Vec<&str> = result.iter().map(|t| t.value.as_str()).collect();
+        assert_eq!(values, vec!["x", "=", "1"]);
+    }
+
+    #[test]
+    fn emit_produces_whitespace_and_newline_tokens() {
+        let tokenizer = get_tokenizer(WhitespacePolicy::Emit);
+        let input = "x  = 1\r\n\ty\n";
+        let result = tokenizer.tokenize(input).expect("Tokenization failed");
+
+        let tokens: Vec<(&str, &str)> = result
+            .iter()
+            .map(|t| (t.token_type.as_str(), t.value.as_str()))
+            .collect();
+        assert_eq!(
+            tokens,
+            vec![
+                ("Identifier", "x"),
+                (WHITESPACE_TOKEN_TYPE, "  "),
+                ("Operator", "="),
+                (WHITESPACE_TOKEN_TYPE, " "),
+                ("Number", "1"),
+                (NEWLINE_TOKEN_TYPE, "\r\n"),
+                (WHITESPACE_TOKEN_TYPE, "\t"),
+                ("Identifier", "y"),
+                (NEWLINE_TOKEN_TYPE, "\n"),
+            ]
+        );
+
+        // Emitted tokens cover the input without gaps
+        let text: String = result.iter().map(|t| t.text(input)).collect();
+        assert_eq!(text, input);
+
+        let y = &result[7];
+        assert_eq!((y.line, y.column), (2, 2));
+        assert_eq!(y.span, Span::new(9, 10));
+    }
+
+    #[test]
+    fn delegate_passes_whitespace_to_the_rules() {
+        let mut tokenizer = get_tokenizer(WhitespacePolicy::Delegate);
+        tokenizer.add_regex_rule(r"^ +", "Indent", None);
+
+        let result = tokenizer.tokenize("  x").expect("Tokenization failed");
+        let types: Vec<&str> = result.iter().map(|t| t.token_type.as_str()).collect();
+        assert_eq!(types, vec!["Indent", "Identifier"]);
+
+        // Without a rule for it, whitespace is unrecognized
+        assert!(get_tokenizer(WhitespacePolicy::Delegate)
+            .tokenize("x =")
+            .is_err());
+    }
+
+    #[test]
+    fn mode_policy_overrides_the_default() {
+        let mut tokenizer = get_tokenizer(WhitespacePolicy::Skip);
+        tokenizer
+            .add_symbol_rule("\"", "Quote", None)
+            .push_mode("string");
+        tokenizer.with_mode("string", |t| {
+            t.set_whitespace_policy(WhitespacePolicy::Delegate);
+            t.add_symbol_rule("\"", "Quote", None).pop_mode();
+            t.add_regex_rule(r#"^[^"]+"#, "Text", None);
+        });
+
+        assert_eq!(
+            tokenizer.whitespace_policy("string"),
+            WhitespacePolicy::Delegate
+        );
+        assert_eq!(
+            tokenizer.whitespace_policy("default"),
+            WhitespacePolicy::Skip
+        );
+
+        let result = tokenizer
+            .tokenize("x = \" a b \"")
+            .expect("Tokenization failed");
+        let values: Vec<&str> = result.iter().map(|t| t.value.as_str()).collect();
+        assert_eq!(values, vec!["x", "=", "\"", " a b ", "\""]);
+    }
+
+    #[test]
+    fn custom_whitespace_chars() {
+        let mut tokenizer = get_tokenizer(WhitespacePolicy::Skip);
+        tokenizer.set_whitespace_chars(&[' ', ',']);
+        tokenizer.set_recovery_policy(RecoveryPolicy::SkipToWhitespace);
+
+        assert!(tokenizer.is_whitespace(','));
+        assert!(!tokenizer.is_whitespace('\t'));
+
+        let (tokens, errors) = tokenizer.tokenize_with_diagnostics("1,2 ,\t3@4,5");
+        let values: Vec<&str> = tokens.iter().map(|t| t.value.as_str()).collect();
+        assert_eq!(values, vec!["1", "2", "\t3@4", "5"]);
+        assert_eq!(errors.len(), 1);
+    }
+}